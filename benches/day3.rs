@@ -0,0 +1,115 @@
+//! Compares day 3's bitset grid and streaming counter with the approach they
+//! replaced: every row copied out to the right and indexed with `chars().nth`.
+//!
+//! Run with `cargo bench --bench day3`.
+
+use aoc2020::{
+    bench::DEFAULT_ITERATIONS,
+    day3::{self, Day3, Slope},
+    Solution,
+};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const WIDTH: usize = 31;
+
+const SLOPES: [Slope; 5] = [
+    Slope { right: 1, down: 1 },
+    Slope { right: 3, down: 1 },
+    Slope { right: 5, down: 1 },
+    Slope { right: 7, down: 1 },
+    Slope { right: 1, down: 2 },
+];
+
+fn main() {
+    println!("{:<28}  {:>9}  {:>12}", "approach", "rows", "median");
+    for &rows in [323, 2_000].iter() {
+        let map = generate(rows);
+        report("expanded strings", rows, || old_total(&map));
+        report("bitset grid", rows, || new_total(&map));
+        report("stream", rows, || stream_total(&map));
+    }
+
+    // Copying the rows out far enough for the steepest slope takes
+    // rows * 7 / 31 copies of every row, so only the new approaches scale.
+    let rows = 1_000_000;
+    let map = generate(rows);
+    report("bitset grid", rows, || new_total(&map));
+    report("stream", rows, || stream_total(&map));
+}
+
+fn report(approach: &str, rows: usize, mut run: impl FnMut() -> i64) {
+    let mut samples: Vec<Duration> = (0..DEFAULT_ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .collect();
+    samples.sort_unstable();
+    println!(
+        "{:<28}  {:>9}  {:>12?}",
+        approach,
+        rows,
+        samples[samples.len() / 2]
+    );
+}
+
+/// A pseudo-random map with roughly one tree in four squares.
+fn generate(rows: usize) -> String {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut map = String::with_capacity(rows * (WIDTH + 1));
+    for _ in 0..rows {
+        for _ in 0..WIDTH {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            map.push(if state.is_multiple_of(4) { '#' } else { '.' });
+        }
+        map.push('\n');
+    }
+    map
+}
+
+fn new_total(input: &str) -> i64 {
+    let map = Day3.parse(input).unwrap();
+    SLOPES
+        .iter()
+        .map(|slope| day3::tree_count_for_slope(&map, slope) as i64)
+        .sum()
+}
+
+fn stream_total(input: &str) -> i64 {
+    day3::stream_tree_counts(input.as_bytes(), &SLOPES)
+        .unwrap()
+        .into_iter()
+        .map(i64::from)
+        .sum()
+}
+
+/// The original parse and count, repeating each row enough times to fit the
+/// steepest slope rather than a fixed 81.
+fn old_total(input: &str) -> i64 {
+    let rows = input.lines().count();
+    let copies = rows * 7 / WIDTH + 1;
+    let map: Vec<String> = input.lines().map(|line| line.repeat(copies)).collect();
+    SLOPES
+        .iter()
+        .map(|slope| {
+            let mut index = 0;
+            let mut trees = 0i64;
+            for (n, layer) in map.iter().step_by(slope.down).enumerate() {
+                if n == 0 {
+                    continue;
+                }
+                index += slope.right;
+                if layer.chars().nth(index).unwrap() == '#' {
+                    trees += 1;
+                }
+            }
+            trees
+        })
+        .sum()
+}
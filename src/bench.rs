@@ -0,0 +1,138 @@
+use crate::{
+    error::ParseError,
+    solution::{Puzzle, Timing},
+};
+use serde::Serialize;
+use std::time::Duration;
+
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    pub min_ns: u128,
+    pub median_ns: u128,
+    pub max_ns: u128,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let len = samples.len();
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+        Self {
+            min_ns: samples[0].as_nanos(),
+            median_ns: median.as_nanos(),
+            max_ns: samples[len - 1].as_nanos(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+}
+
+/// Times a puzzle's parse and both parts over `iterations` runs.
+pub fn run(puzzle: &dyn Puzzle, input: &str, iterations: usize) -> Result<Report, ParseError> {
+    let timings = (0..iterations.max(1))
+        .map(|_| puzzle.time(input))
+        .collect::<Result<Vec<Timing>, _>>()?;
+    Ok(summarize(puzzle.day(), &timings))
+}
+
+fn summarize(day: u8, timings: &[Timing]) -> Report {
+    let samples = |stage: fn(&Timing) -> Duration| timings.iter().map(stage).collect();
+    Report {
+        day,
+        iterations: timings.len(),
+        parse: Stats::from_samples(samples(|timing| timing.parse)),
+        part_one: Stats::from_samples(samples(|timing| timing.part_one)),
+        part_two: Stats::from_samples(samples(|timing| timing.part_two)),
+    }
+}
+
+pub fn print_table(reports: &[Report]) {
+    println!(
+        "{:>3}  {:<8}  {:>12}  {:>12}  {:>12}",
+        "day", "stage", "min", "median", "max"
+    );
+    for report in reports {
+        let stages = [
+            ("parse", report.parse),
+            ("part A", report.part_one),
+            ("part B", report.part_two),
+        ];
+        for (stage, stats) in stages.iter() {
+            println!(
+                "{:>3}  {:<8}  {:>12}  {:>12}  {:>12}",
+                report.day,
+                stage,
+                format_ns(stats.min_ns),
+                format_ns(stats.median_ns),
+                format_ns(stats.max_ns)
+            );
+        }
+    }
+}
+
+pub fn print_json(reports: &[Report]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(reports).map_err(|err| err.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn format_ns(ns: u128) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{summarize, Stats};
+    use crate::solution::Timing;
+    use std::time::Duration;
+
+    fn timing(parse: u64, part_one: u64, part_two: u64) -> Timing {
+        Timing {
+            parse: Duration::from_nanos(parse),
+            part_one: Duration::from_nanos(part_one),
+            part_two: Duration::from_nanos(part_two),
+        }
+    }
+
+    #[test]
+    fn summarize_reports_min_median_max_per_stage() {
+        let report = summarize(3, &[timing(5, 10, 1), timing(1, 30, 2), timing(3, 20, 9)]);
+        assert_eq!(report.iterations, 3);
+        assert_eq!(
+            report.parse,
+            Stats {
+                min_ns: 1,
+                median_ns: 3,
+                max_ns: 5
+            }
+        );
+        assert_eq!(report.part_one.median_ns, 20);
+        assert_eq!(report.part_two.max_ns, 9);
+    }
+
+    #[test]
+    fn median_of_even_samples_is_the_mean_of_the_middle_two() {
+        let report = summarize(3, &[timing(1, 0, 0), timing(4, 0, 0)]);
+        assert_eq!(report.parse.median_ns, 2);
+    }
+
+    #[test]
+    fn reports_serialize_to_json() {
+        let report = summarize(7, &[timing(1, 2, 3)]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with("{\"day\":7,\"iterations\":1,\"parse\":{\"min_ns\":1,"));
+    }
+}
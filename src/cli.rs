@@ -0,0 +1,647 @@
+use crate::scaffold;
+use aoc2020::{
+    bench,
+    day1::Day1,
+    day2::{
+        self, CharClass, Checker, Emit, ExactlyOnePosition, ForbiddenSubstrings, LengthRange,
+        OccurrenceRange, PasswordPolicy, RequiredClasses,
+    },
+    day3::{self, Day3, Slope},
+    day4::{self, Day4, Schema},
+    day5::Day5,
+    day9::Day9,
+    input::{self, Source},
+    registry,
+    seating::SeatingChart,
+    solution::{Part, Puzzle, Solution},
+    verify,
+};
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::PathBuf,
+    slice,
+};
+
+const USAGE: &str = "usage:
+    aoc2020 run <day|first..last|all>... [--part a|b] [--input <file>|-]
+    aoc2020 run 1 [--target <sum>] [--size <k>] [--aggregate product|sum|list] [...]
+    aoc2020 run 4 [--schema <file>] [...]
+    aoc2020 run 9 [--preamble <n>] [...]
+    aoc2020 verify [<day|first..last|all>...] [--answers <file>] [--preamble <n>]
+    aoc2020 bench [<day|first..last|all>...] [--iterations <n>] [--json] [--input <file>|-]
+    aoc2020 audit [--input <file>|-] [--policy occurrence|position]... [--length <min>-<max>]
+                  [--require lower,upper,digit,symbol] [--forbid <text>]... [--valid|--invalid]
+    aoc2020 slopes [--input <file>|-] [--right <first..last>] [--down <first..last>]
+                   [--show <right>,<down>]
+    aoc2020 passports [--input <file>|-] [--schema <file>] [--json]
+    aoc2020 export [--input <file>|-] [--schema <file>] [--format csv|json] [--output <file>]
+    aoc2020 seats [--input <file>|-]
+    aoc2020 new <day>
+
+Inputs are read from $AOC_DATA_DIR/dayN.txt, or data/dayN.txt when unset.
+Expected answers are read from answers.toml unless --answers is given.
+With --size, day 1 makes a single search for that many entries and prints one answer.
+Day 4 validates passports against schemas/passport.toml's rules unless --schema is given.
+Day 9 checks each number against the 25 before it unless --preamble gives another count.
+`audit` streams a day 2 password database and reports which records break which
+policies (both puzzle policies unless others are given); with --valid or --invalid it
+prints those records and writes the report to stderr.
+`slopes` searches day 3 slopes (right 0..7, down 1..2 by default) for the fewest and
+most trees, and with --show draws one slope's path with the trees hit marked `X`.
+`passports` lists every missing, duplicated, unknown or invalid field of each day 4
+passport and the rule it broke.
+`export` writes day 4's valid passports, heights in centimetres, as CSV (the default)
+or JSON to stdout or --output.
+`seats` draws day 5's plane (`#` taken, `.` empty, `!` issued twice) and lists the
+duplicated passes and the empty seats between the first and last taken ones.
+`new` must be run from the project root; it generates src/dayN.rs and registers it.";
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = split_options(args);
+    match args.first().map(String::as_str) {
+        Some("run") => run_days(&args[1..]),
+        Some("verify") => verify_answers(&args[1..]),
+        Some("bench") => bench_days(&args[1..]),
+        Some("audit") => audit_passwords(&args[1..]),
+        Some("slopes") => explore_slopes(&args[1..]),
+        Some("passports") => diagnose_passports(&args[1..]),
+        Some("export") => export_passports(&args[1..]),
+        Some("seats") => chart_seats(&args[1..]),
+        Some("new") => new_day(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command `{}`\n{}", command, USAGE)),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+fn run_days(args: &[String]) -> Result<(), String> {
+    let mut days = Vec::new();
+    let mut part = None;
+    let mut source = Source::DataDir;
+    let mut day1 = None;
+    let mut sized = false;
+    let mut day4 = None;
+    let mut day9 = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => part = Some(option_value(&mut args, arg)?.parse()?),
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            "--target" | "--size" | "--aggregate" => {
+                let day1 = day1.get_or_insert_with(Day1::default);
+                configure_day1(day1, arg, option_value(&mut args, arg)?)?;
+                sized |= arg == "--size";
+            }
+            "--schema" => {
+                let schema = Schema::load(&PathBuf::from(option_value(&mut args, arg)?))?;
+                day4 = Some(Day4 { schema });
+            }
+            "--preamble" => {
+                let preamble = parse_preamble(option_value(&mut args, arg)?)?;
+                day9 = Some(Day9 { preamble });
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => days.append(&mut parse_days(arg)?),
+        }
+    }
+
+    if days.is_empty() {
+        return Err(format!("no day given\n{}", USAGE));
+    }
+    if let Some(day1) = day1 {
+        if days.len() != 1 || days[0].day() != 1 {
+            return Err("`--target`, `--size` and `--aggregate` only apply to day 1".to_string());
+        }
+        days = vec![Box::new(day1)];
+    }
+    if let Some(day4) = day4 {
+        if days.len() != 1 || days[0].day() != 4 {
+            return Err("`--schema` only applies to day 4".to_string());
+        }
+        days = vec![Box::new(day4)];
+    }
+    if let Some(day9) = day9 {
+        if days.len() != 1 || days[0].day() != 9 {
+            return Err("`--preamble` only applies to day 9".to_string());
+        }
+        days = vec![Box::new(day9)];
+    }
+    if days.len() > 1 && source != Source::DataDir {
+        return Err("`--input` can only be used with a single day".to_string());
+    }
+
+    // With `--size` both parts search for the same tuple size, so only print one.
+    let parts = match part {
+        Some(part) => vec![part],
+        None if sized => vec![Part::One],
+        None => vec![Part::One, Part::Two],
+    };
+    for puzzle in days {
+        run_day(puzzle.as_ref(), &parts, &source)?;
+    }
+    Ok(())
+}
+
+fn configure_day1(day1: &mut Day1, option: &str, value: &str) -> Result<(), String> {
+    match option {
+        "--target" => {
+            day1.target = value
+                .parse()
+                .map_err(|_| format!("`{}` is not a valid target", value))?
+        }
+        "--size" => match value.parse() {
+            Ok(size) if size > 0 => day1.sizes = [size, size],
+            _ => return Err(format!("`{}` is not a valid tuple size", value)),
+        },
+        _ => day1.aggregate = value.parse()?,
+    }
+    Ok(())
+}
+
+fn parse_preamble(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid preamble", value))
+}
+
+fn run_day(puzzle: &dyn Puzzle, parts: &[Part], source: &Source) -> Result<(), String> {
+    let input = input::read(puzzle.day(), source).map_err(|err| err.to_string())?;
+
+    let answers = puzzle.solve(&input, parts).map_err(|err| err.to_string())?;
+
+    let mut line = format!("Day {}", puzzle.day());
+    for (part, answer) in parts.iter().zip(answers) {
+        line.push_str(&format!(" - {}: {}", part, answer));
+    }
+    println!("{}", line);
+    Ok(())
+}
+
+fn verify_answers(args: &[String]) -> Result<(), String> {
+    let mut days = Vec::new();
+    let mut answers = PathBuf::from(verify::DEFAULT_ANSWERS_FILE);
+    let mut puzzles = registry::all();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" | "-a" => answers = PathBuf::from(option_value(&mut args, arg)?),
+            "--preamble" => {
+                let preamble = parse_preamble(option_value(&mut args, arg)?)?;
+                for puzzle in puzzles.iter_mut().filter(|puzzle| puzzle.day() == 9) {
+                    *puzzle = Box::new(Day9 { preamble });
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => days.extend(parse_days(arg)?.iter().map(|puzzle| puzzle.day())),
+        }
+    }
+
+    let mut expected = verify::load(&answers)?;
+    if !days.is_empty() {
+        expected.retain(|exp| days.contains(&exp.day));
+    }
+    if expected.is_empty() {
+        return Err(format!(
+            "no expected answers to verify in {}",
+            answers.display()
+        ));
+    }
+
+    let checks = verify::check(&expected, &puzzles);
+    verify::print_table(&checks);
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    if failed > 0 {
+        return Err(format!("{} of {} answers failed", failed, checks.len()));
+    }
+    println!("all {} answers match", checks.len());
+    Ok(())
+}
+
+fn bench_days(args: &[String]) -> Result<(), String> {
+    let mut days = Vec::new();
+    let mut iterations = bench::DEFAULT_ITERATIONS;
+    let mut json = false;
+    let mut source = Source::DataDir;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" | "-n" => {
+                let value = option_value(&mut args, arg)?;
+                iterations = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("`{}` is not a positive iteration count", value)),
+                };
+            }
+            "--json" => json = true,
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => days.append(&mut parse_days(arg)?),
+        }
+    }
+
+    if days.is_empty() {
+        days = registry::all();
+    }
+    if days.len() > 1 && source != Source::DataDir {
+        return Err("`--input` can only be used with a single day".to_string());
+    }
+
+    let mut reports = Vec::with_capacity(days.len());
+    for puzzle in days {
+        let input = input::read(puzzle.day(), &source).map_err(|err| err.to_string())?;
+        let report = bench::run(puzzle.as_ref(), &input, iterations);
+        reports.push(report.map_err(|err| err.to_string())?);
+    }
+
+    if json {
+        bench::print_json(&reports)
+    } else {
+        bench::print_table(&reports);
+        Ok(())
+    }
+}
+
+/// Splits `--option=value` into `--option value` so both spellings parse alike.
+fn split_options(args: &[String]) -> Vec<String> {
+    let mut split = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => {
+                split.push(option.to_string());
+                split.push(value.to_string());
+            }
+            _ => split.push(arg.clone()),
+        }
+    }
+    split
+}
+
+fn option_value<'a>(args: &mut slice::Iter<'a, String>, option: &str) -> Result<&'a str, String> {
+    args.next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("`{}` expects a value", option))
+}
+
+/// Parses a single day (`14`), an inclusive range (`3..9` or `3..=9`) or `all`.
+fn parse_days(spec: &str) -> Result<Vec<Box<dyn Puzzle>>, String> {
+    if spec == "all" {
+        return Ok(registry::all());
+    }
+
+    let (first, last) = match spec.split_once("..") {
+        Some((first, last)) => (parse_day(first)?, parse_day(last.trim_start_matches('='))?),
+        None => {
+            let day = parse_day(spec)?;
+            (day, day)
+        }
+    };
+    if first > last {
+        return Err(format!(
+            "invalid range `{}`: {} comes after {}",
+            spec, first, last
+        ));
+    }
+
+    (first..=last)
+        .map(|number| {
+            registry::find(number).ok_or_else(|| {
+                format!(
+                    "day {} is not implemented (available: 1-{})",
+                    number,
+                    registry::all().len()
+                )
+            })
+        })
+        .collect()
+}
+
+fn audit_passwords(args: &[String]) -> Result<(), String> {
+    let mut source = Source::DataDir;
+    let mut policies: Vec<Box<dyn PasswordPolicy>> = Vec::new();
+    let mut forbidden = Vec::new();
+    let mut emit = Emit::Nothing;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            "--policy" => match option_value(&mut args, arg)? {
+                "occurrence" => policies.push(Box::new(OccurrenceRange)),
+                "position" => policies.push(Box::new(ExactlyOnePosition)),
+                policy => return Err(format!("unknown policy `{}`", policy)),
+            },
+            "--length" => {
+                let value = option_value(&mut args, arg)?;
+                let bounds = value.split_once('-').and_then(|(min, max)| {
+                    Some(LengthRange {
+                        min: min.parse().ok()?,
+                        max: max.parse().ok()?,
+                    })
+                });
+                policies.push(Box::new(bounds.ok_or_else(|| {
+                    format!("`{}` is not a length range like `8-64`", value)
+                })?));
+            }
+            "--require" => {
+                let classes = option_value(&mut args, arg)?
+                    .split(',')
+                    .map(str::parse)
+                    .collect::<Result<Vec<CharClass>, _>>()?;
+                policies.push(Box::new(RequiredClasses(classes)));
+            }
+            "--forbid" => forbidden.push(option_value(&mut args, arg)?.to_string()),
+            "--valid" => emit = Emit::Valid,
+            "--invalid" => emit = Emit::Invalid,
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    if !forbidden.is_empty() {
+        policies.push(Box::new(ForbiddenSubstrings(forbidden)));
+    }
+    if policies.is_empty() {
+        policies = vec![Box::new(OccurrenceRange), Box::new(ExactlyOnePosition)];
+    }
+
+    let reader = input::open(2, &source).map_err(|err| err.to_string())?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let report = day2::audit(reader, &Checker::new(policies), emit, &mut out)
+        .map_err(|err| format!("could not read password database: {}", err))?;
+    if emit == Emit::Nothing {
+        write!(out, "{}", report).map_err(|err| err.to_string())?;
+    } else {
+        eprint!("{}", report);
+    }
+    Ok(())
+}
+
+fn explore_slopes(args: &[String]) -> Result<(), String> {
+    let mut source = Source::DataDir;
+    let mut rights = 0..=7;
+    let mut downs = 1..=2;
+    let mut show = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            "--right" => rights = parse_steps(option_value(&mut args, arg)?)?,
+            "--down" => downs = parse_steps(option_value(&mut args, arg)?)?,
+            "--show" => {
+                let value = option_value(&mut args, arg)?;
+                let (right, down) = value.split_once(',').ok_or_else(|| {
+                    format!("`{}` is not a slope like `3,1` (right, down)", value)
+                })?;
+                show = Some(Slope {
+                    right: parse_step(right, value)?,
+                    down: parse_step(down, value)?,
+                });
+            }
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    if *downs.start() == 0 || show.is_some_and(|slope| slope.down == 0) {
+        return Err("a slope has to go down at least 1 each step".to_string());
+    }
+
+    let input = input::read(Day3::DAY, &source).map_err(|err| err.to_string())?;
+    let map = Day3.parse(&input).map_err(|err| err.to_string())?;
+    if let Some(slope) = show {
+        print!("{}", day3::render_path(&map, &slope));
+        println!(
+            "right {}, down {}: {} trees",
+            slope.right,
+            slope.down,
+            day3::tree_count_for_slope(&map, &slope)
+        );
+        return Ok(());
+    }
+
+    let extremes = day3::search_slopes(&map, rights, downs).ok_or("no slopes to search")?;
+    for (label, (slope, trees)) in [("fewest", extremes.fewest), ("most", extremes.most)].iter() {
+        println!(
+            "{:<6}  right {}, down {}: {} trees",
+            label, slope.right, slope.down, trees
+        );
+    }
+    Ok(())
+}
+
+/// Steps beyond this are wider or taller than any map worth exploring, and
+/// searching up to them would take forever.
+const MAX_STEP: usize = 10_000;
+
+/// Parses an inclusive range of steps, `first..last`, or a single step.
+fn parse_steps(spec: &str) -> Result<std::ops::RangeInclusive<usize>, String> {
+    match spec.split_once("..") {
+        Some((first, last)) => {
+            Ok(parse_step(first, spec)?..=parse_step(last.trim_start_matches('='), spec)?)
+        }
+        None => parse_step(spec, spec).map(|step| step..=step),
+    }
+}
+
+fn parse_step(text: &str, spec: &str) -> Result<usize, String> {
+    match text.parse() {
+        Ok(step) if step <= MAX_STEP => Ok(step),
+        Ok(_) => Err(format!(
+            "`{}` is more than {} in `{}`",
+            text, MAX_STEP, spec
+        )),
+        Err(_) => Err(format!("`{}` is not a valid step in `{}`", text, spec)),
+    }
+}
+
+fn diagnose_passports(args: &[String]) -> Result<(), String> {
+    let mut source = Source::DataDir;
+    let mut day = Day4::default();
+    let mut json = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            "--schema" => day.schema = Schema::load(&PathBuf::from(option_value(&mut args, arg)?))?,
+            "--json" => json = true,
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+
+    let input = input::read(Day4::DAY, &source).map_err(|err| err.to_string())?;
+    let passports = day.parse(&input).map_err(|err| err.to_string())?;
+    let diagnoses = day4::diagnose(&day.schema, &passports);
+    if json {
+        day4::print_json(&diagnoses)
+    } else {
+        day4::print_table(&diagnoses);
+        Ok(())
+    }
+}
+
+fn export_passports(args: &[String]) -> Result<(), String> {
+    let mut source = Source::DataDir;
+    let mut day = Day4::default();
+    let mut json = false;
+    let mut output = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            "--schema" => day.schema = Schema::load(&PathBuf::from(option_value(&mut args, arg)?))?,
+            "--format" => match option_value(&mut args, arg)? {
+                "csv" => json = false,
+                "json" => json = true,
+                format => return Err(format!("unknown format `{}`", format)),
+            },
+            "--output" | "-o" => output = Some(PathBuf::from(option_value(&mut args, arg)?)),
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+
+    let input = input::read(Day4::DAY, &source).map_err(|err| err.to_string())?;
+    let records = day.parse(&input).map_err(|err| err.to_string())?;
+    let mut passports = Vec::new();
+    for (number, passport) in day4::valid_passports(&day.schema, &records) {
+        match passport {
+            Ok(passport) => passports.push(passport),
+            Err(err) => eprintln!("skipped passport {}: {}", number, err),
+        }
+    }
+
+    let mut out: Box<dyn Write> = match &output {
+        Some(path) => Box::new(
+            fs::File::create(path)
+                .map_err(|err| format!("could not create {}: {}", path.display(), err))?,
+        ),
+        None => Box::new(io::stdout()),
+    };
+    let written = if json {
+        day4::write_json(&passports, &mut out)
+    } else {
+        day4::write_csv(&passports, &mut out)
+    };
+    written.map_err(|err| format!("could not write passports: {}", err))
+}
+
+fn chart_seats(args: &[String]) -> Result<(), String> {
+    let mut source = Source::DataDir;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => source = Source::from_arg(option_value(&mut args, arg)?),
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+
+    let day = Day5::default();
+    let input = input::read(Day5::DAY, &source).map_err(|err| err.to_string())?;
+    let passes = day.parse(&input).map_err(|err| err.to_string())?;
+    let chart = SeatingChart::new(day.geometry, &passes);
+    print!("{}", chart.render());
+
+    for (seat, count) in chart.duplicates() {
+        println!("{} (seat {}) was issued {} times", seat, seat.id(), count);
+    }
+    let vacancies = chart.vacancies();
+    if let (Some(first), Some(last)) = (vacancies.front.first(), vacancies.front.last()) {
+        println!(
+            "missing from the front: {} seats, rows {}-{}",
+            vacancies.front.len(),
+            first.row,
+            last.row
+        );
+    }
+    if let (Some(first), Some(last)) = (vacancies.back.first(), vacancies.back.last()) {
+        println!(
+            "missing from the back: {} seats, rows {}-{}",
+            vacancies.back.len(),
+            first.row,
+            last.row
+        );
+    }
+    for seat in vacancies.gaps {
+        println!(
+            "empty: row {}, column {}, seat {} ({})",
+            seat.row,
+            seat.column,
+            seat.id(),
+            seat
+        );
+    }
+    Ok(())
+}
+
+fn new_day(args: &[String]) -> Result<(), String> {
+    let day = match args {
+        [day] => parse_day(day)?,
+        _ => return Err(format!("expected a single day\n{}", USAGE)),
+    };
+    let root = env::current_dir().map_err(|err| err.to_string())?;
+    for path in scaffold::new_day(&root, day)? {
+        let path = path.strip_prefix(&root).unwrap_or(&path);
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`{}` is not a day number", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_days, parse_steps, split_options};
+    use aoc2020::registry;
+
+    fn numbers(spec: &str) -> Vec<u8> {
+        parse_days(spec)
+            .unwrap()
+            .iter()
+            .map(|puzzle| puzzle.day())
+            .collect()
+    }
+
+    #[test]
+    fn parse_days_works() {
+        assert_eq!(numbers("14"), vec![14]);
+        assert_eq!(numbers("3..9"), vec![3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(numbers("3..=5"), vec![3, 4, 5]);
+        assert_eq!(numbers("all").len(), registry::all().len());
+    }
+
+    #[test]
+    fn parse_days_rejects_unknown_days() {
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("18..26").is_err());
+        assert!(parse_days("9..3").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn parse_steps_works() {
+        assert_eq!(parse_steps("0..7"), Ok(0..=7));
+        assert_eq!(parse_steps("2..=3"), Ok(2..=3));
+        assert_eq!(parse_steps("4"), Ok(4..=4));
+        assert!(parse_steps("1..x").is_err());
+        assert!(parse_steps("0..10001").is_err());
+        assert!(parse_steps("18446744073709551615").is_err());
+    }
+
+    #[test]
+    fn split_options_separates_inline_values() {
+        let args: Vec<String> = vec!["run".into(), "--part=b".into(), "-i".into(), "-".into()];
+        assert_eq!(split_options(&args), vec!["run", "--part", "b", "-i", "-"]);
+    }
+}
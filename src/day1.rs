@@ -1,47 +1,350 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::{collections::HashMap, str::FromStr};
 
-const SUM: i32 = 2020;
-
-pub fn execute() {
-    part_one();
-    part_two();
+/// How a selection of entries is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Product,
+    Sum,
+    /// The entries themselves, comma separated.
+    List,
 }
 
-fn part_one() {
-    let entries = get_entries();
-    if let Some((entry1, entry2)) = entries_equaling_sum(&entries[..], SUM) {
-        print!("Day 1 - A: {}", entry1 * entry2);
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "product" => Ok(Aggregate::Product),
+            "sum" => Ok(Aggregate::Sum),
+            "list" => Ok(Aggregate::List),
+            _ => Err(format!(
+                "unknown aggregate `{}`, expected `product`, `sum` or `list`",
+                value
+            )),
+        }
     }
 }
 
-fn part_two() {
-    let entries = get_entries();
+impl Aggregate {
+    fn answer(self, selection: &Selection) -> Answer {
+        match self {
+            Aggregate::Product => selection.product.into(),
+            Aggregate::Sum => selection
+                .entries
+                .iter()
+                .map(|&entry| entry as i64)
+                .sum::<i64>()
+                .into(),
+            Aggregate::List => selection
+                .entries
+                .iter()
+                .map(i32::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+                .into(),
+        }
+    }
+}
 
-    for (i, entry1) in entries.iter().enumerate() {
-        let sublist = &entries[i..];
-        let remainder = SUM - entry1;
+/// Finds entries of the expense report that add up to `target`: `sizes[0]` of them
+/// for part one and `sizes[1]` for part two.
+pub struct Day1 {
+    pub target: i64,
+    pub sizes: [usize; 2],
+    pub aggregate: Aggregate,
+}
 
-        if let Some((entry2, entry3)) = entries_equaling_sum(sublist, remainder) {
-            println!(", B: {}", entry1 * entry2 * entry3);
+impl Default for Day1 {
+    fn default() -> Self {
+        Self {
+            target: 2020,
+            sizes: [2, 3],
+            aggregate: Aggregate::Product,
         }
     }
 }
 
-fn entries_equaling_sum(entries: &[i32], sum: i32) -> Option<(i32, i32)> {
-    for (i, entry) in entries.iter().enumerate() {
-        let remainder = sum - entry;
-        let sublist = &entries[i..];
-        if let Some(other_entry) = sublist.iter().filter(|&entry| *entry == remainder).next() {
-            return Some((*entry, *other_entry));
+impl Day1 {
+    fn solve(&self, entries: &[i32], k: usize) -> Answer {
+        find(entries, k, self.target)
+            .map(|selection| self.aggregate.answer(&selection))
+            .into()
+    }
+}
+
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+
+    const DAY: u8 = 1;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input.lines().map(|line| ctx.number(line)).collect()
+    }
+
+    fn part_one(&self, entries: &Vec<i32>) -> Answer {
+        self.solve(entries, self.sizes[0])
+    }
+
+    fn part_two(&self, entries: &Vec<i32>) -> Answer {
+        self.solve(entries, self.sizes[1])
+    }
+}
+
+/// Entries chosen by `find`, in index order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub indices: Vec<usize>,
+    pub entries: Vec<i32>,
+    /// The entries multiplied together, or `None` if that overflows an `i64`.
+    pub product: Option<i64>,
+}
+
+/// Finds the first selection of `k` entries that sum to `target`.
+///
+/// Every entry is used at most once: a selection never repeats an index, but equal
+/// values at different indices may both be chosen, so two 1010s sum to 2020 while
+/// a single 1010 does not. "First" is the selection whose indices come first in
+/// lexicographic order.
+pub fn find(entries: &[i32], k: usize, target: i64) -> Option<Selection> {
+    let indices = k_sum(entries, k, target, Search::First).pop()?;
+    let entries: Vec<i32> = indices.iter().map(|&i| entries[i]).collect();
+    Some(Selection {
+        product: entries
+            .iter()
+            .try_fold(1i64, |product, &entry| product.checked_mul(entry as i64)),
+        indices,
+        entries,
+    })
+}
+
+/// How many of the matching tuples `k_sum` collects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Search {
+    First,
+    All,
+}
+
+/// Finds every tuple (or just the first) of `k` different entries that sum to
+/// `target`, as strictly increasing indices into `entries`, in lexicographic
+/// order. No tuples means no solution.
+///
+/// Pairs are looked up in a hash from each value to its indices; larger tuples
+/// fix their first entry and search the rest for a `k - 1` tuple.
+pub fn k_sum(entries: &[i32], k: usize, target: i64, search: Search) -> Vec<Vec<usize>> {
+    let mut found = Vec::new();
+    let mut prefix = Vec::with_capacity(k);
+    search_from(entries, 0, k, target, search, &mut prefix, &mut found);
+    found
+}
+
+fn search_from(
+    entries: &[i32],
+    start: usize,
+    k: usize,
+    target: i64,
+    search: Search,
+    prefix: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+) {
+    let done = |found: &Vec<Vec<usize>>| search == Search::First && !found.is_empty();
+    let with = |prefix: &Vec<usize>, indices: &[usize]| [&prefix[..], indices].concat();
+
+    match k {
+        0 => {
+            if target == 0 {
+                found.push(prefix.clone());
+            }
+        }
+        1 => {
+            for (i, &entry) in entries.iter().enumerate().skip(start) {
+                if entry as i64 == target {
+                    found.push(with(prefix, &[i]));
+                    if done(found) {
+                        return;
+                    }
+                }
+            }
+        }
+        2 => {
+            let mut positions: HashMap<i64, Vec<usize>> = HashMap::new();
+            for (j, &entry) in entries.iter().enumerate().skip(start) {
+                positions.entry(entry as i64).or_default().push(j);
+            }
+            for (i, &entry) in entries.iter().enumerate().skip(start) {
+                let others = target
+                    .checked_sub(entry as i64)
+                    .and_then(|rest| positions.get(&rest));
+                if let Some(others) = others {
+                    let after = others.partition_point(|&j| j <= i);
+                    for &j in &others[after..] {
+                        found.push(with(prefix, &[i, j]));
+                        if done(found) {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+        _ => {
+            for i in start..entries.len() {
+                // A remainder beyond i64 is out of reach of the other entries.
+                let remainder = match target.checked_sub(entries[i] as i64) {
+                    Some(remainder) => remainder,
+                    None => continue,
+                };
+                prefix.push(i);
+                search_from(entries, i + 1, k - 1, remainder, search, prefix, found);
+                prefix.pop();
+                if done(found) {
+                    return;
+                }
+            }
         }
     }
-    None
 }
 
-fn get_entries() -> Vec<i32> {
-    fs::read_to_string("data/day1.txt")
-        .unwrap()
-        .lines()
-        .map(|line| line.parse::<i32>().unwrap())
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::{find, k_sum, Aggregate, Day1, Search, Selection};
+    use crate::solution::{Answer, Solution};
+
+    const EXPENSES: [i32; 6] = [1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn k_sum_finds_pairs_and_triples() {
+        assert_eq!(k_sum(&EXPENSES, 2, 2020, Search::First), vec![vec![0, 3]]);
+        assert_eq!(k_sum(&EXPENSES, 3, 2020, Search::All), vec![vec![1, 2, 4]]);
+    }
+
+    #[test]
+    fn k_sum_handles_any_tuple_size() {
+        assert_eq!(
+            k_sum(&EXPENSES, 4, 1721 + 979 + 366 + 675, Search::All),
+            vec![vec![0, 1, 2, 4]]
+        );
+        assert_eq!(k_sum(&EXPENSES, 1, 366, Search::All), vec![vec![2]]);
+        assert_eq!(
+            k_sum(&EXPENSES, 0, 0, Search::All),
+            vec![Vec::<usize>::new()]
+        );
+    }
+
+    #[test]
+    fn k_sum_collects_every_match() {
+        let entries = [1, 4, 2, 3, 0];
+        assert_eq!(
+            k_sum(&entries, 2, 4, Search::All),
+            vec![vec![0, 3], vec![1, 4]]
+        );
+        assert_eq!(k_sum(&entries, 2, 4, Search::First).len(), 1);
+    }
+
+    #[test]
+    fn k_sum_reports_no_solution_as_empty() {
+        assert!(k_sum(&EXPENSES, 2, 1, Search::All).is_empty());
+        assert!(k_sum(&EXPENSES, 7, 2020, Search::First).is_empty());
+    }
+
+    #[test]
+    fn find_returns_entries_indices_and_product() {
+        assert_eq!(
+            find(&EXPENSES, 3, 2020),
+            Some(Selection {
+                indices: vec![1, 2, 4],
+                entries: vec![979, 366, 675],
+                product: Some(241861950),
+            })
+        );
+    }
+
+    #[test]
+    fn find_never_pairs_an_entry_with_itself() {
+        assert_eq!(find(&[1010, 3, 7], 2, 2020), None);
+        let selection = find(&[1010, 3, 1010], 2, 2020).unwrap();
+        assert_eq!(selection.indices, vec![0, 2]);
+        assert_eq!(
+            find(&[500, 1020, 500], 3, 2020).unwrap().indices,
+            vec![0, 1, 2]
+        );
+        assert_eq!(find(&[1000, 20, 1000], 3, 3000), None);
+    }
+
+    #[test]
+    fn find_picks_the_lexicographically_first_selection() {
+        let entries = [1, 2, 3, 1, 2, 3];
+        assert_eq!(find(&entries, 2, 4).unwrap().indices, vec![0, 2]);
+        assert_eq!(find(&entries, 2, 6).unwrap().indices, vec![2, 5]);
+        assert_eq!(k_sum(&entries, 2, 4, Search::All).len(), 5);
+    }
+
+    #[test]
+    fn find_handles_negative_entries() {
+        let selection = find(&[-5, 7, 2025], 2, 2020).unwrap();
+        assert_eq!(selection.entries, vec![-5, 2025]);
+        assert_eq!(selection.product, Some(-10125));
+        assert_eq!(find(&[-1, -2, -3], 2, -5).unwrap().indices, vec![1, 2]);
+    }
+
+    #[test]
+    fn products_that_overflow_are_left_out() {
+        let entries = [2000; 6];
+        let selection = find(&entries, 6, 12000).unwrap();
+        assert_eq!(selection.product, None);
+        assert_eq!(Aggregate::Product.answer(&selection), Answer::None);
+        assert_eq!(
+            Aggregate::List.answer(&selection),
+            Answer::from("2000,2000,2000,2000,2000,2000")
+        );
+        assert_eq!(Aggregate::Sum.answer(&selection), Answer::Number(12000));
+    }
+
+    #[test]
+    fn extreme_targets_find_nothing_instead_of_overflowing() {
+        let entries = [1721, -979, 366, 299];
+        for k in 1..=4 {
+            assert_eq!(find(&entries, k, i64::MIN), None);
+            assert_eq!(find(&entries, k, i64::MAX), None);
+        }
+    }
+
+    #[test]
+    fn find_reports_missing_solutions() {
+        assert_eq!(find(&EXPENSES, 2, 1), None);
+        assert_eq!(find(&[], 2, 2020), None);
+        assert_eq!(find(&[2020], 2, 2020), None);
+    }
+
+    #[test]
+    fn day1_reports_the_configured_aggregate() {
+        let entries = EXPENSES.to_vec();
+        let day1 = Day1 {
+            target: 1041,
+            sizes: [2, 3],
+            aggregate: Aggregate::List,
+        };
+        assert_eq!(day1.part_one(&entries), Answer::from("366,675"));
+        assert_eq!(day1.part_two(&entries), Answer::None);
+
+        let day1 = Day1 {
+            target: 1721 + 979 + 366 + 675,
+            sizes: [4, 4],
+            aggregate: Aggregate::Sum,
+        };
+        assert_eq!(day1.part_one(&entries), Answer::Number(3741));
+        assert_eq!(
+            Day1::default().part_two(&entries),
+            Answer::Number(241861950)
+        );
+    }
+
+    #[test]
+    fn aggregate_from_str_works() {
+        assert_eq!("list".parse(), Ok(Aggregate::List));
+        assert!("mean".parse::<Aggregate>().is_err());
+    }
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fs};
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::collections::HashMap;
 
 type Graph = HashMap<i32, Node>;
 
@@ -17,29 +21,59 @@ impl Node {
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day10;
 
-fn part_one() {
-    let ratings = get_ratings();
-    let diffs = find_differences(&ratings);
-    let dist = diffs.get(&1).unwrap() * diffs.get(&3).unwrap();
-    print!("Day 10 - A: {:?}", dist);
-}
+impl Solution for Day10 {
+    type Input = Vec<i32>;
+
+    const DAY: u8 = 10;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mut lines = input
+            .lines()
+            .map(|line| Ok((line, ctx.number::<i32>(line)?)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        lines.sort_by_key(|&(_, rating)| rating);
+
+        // Every adapter has to take the output of the next lower one, starting
+        // from the outlet's 0 jolts.
+        let mut ratings = vec![0];
+        for (line, rating) in lines {
+            let previous = *ratings.last().unwrap();
+            if rating <= 0 {
+                return Err(ctx.error(line, "expected a rating above 0"));
+            }
+            if rating == previous {
+                return Err(ctx.error(line, "this rating appears twice"));
+            }
+            if rating - previous > 3 {
+                return Err(ctx.error(
+                    line,
+                    format!("expected a rating within 3 jolts of {}", previous),
+                ));
+            }
+            ratings.push(rating);
+        }
+        ratings.push(ratings.last().unwrap() + 3);
+        Ok(ratings)
+    }
+
+    fn part_one(&self, ratings: &Vec<i32>) -> Answer {
+        let diffs = find_differences(ratings);
+        let count = |diff| diffs.get(&diff).copied().unwrap_or(0);
+        let dist = count(1) * count(3);
+        dist.into()
+    }
 
-fn part_two() {
-    let ratings = get_ratings();
-    let final_rating = ratings[ratings.len() - 1];
-    let mut graph = convert_to_graph(&ratings);
-    println!(
-        " - B: {:?}",
-        number_of_combinations(0, &mut graph, final_rating)
-    );
+    fn part_two(&self, ratings: &Vec<i32>) -> Answer {
+        let final_rating = ratings[ratings.len() - 1];
+        let mut graph = convert_to_graph(ratings);
+        number_of_combinations(0, &mut graph, final_rating).into()
+    }
 }
 
-fn find_differences(ratings: &Vec<i32>) -> HashMap<i32, i32> {
+fn find_differences(ratings: &[i32]) -> HashMap<i32, i32> {
     let mut diffs = HashMap::new();
     for pair in ratings.windows(2) {
         let diff = pair[1] - pair[0];
@@ -65,7 +99,7 @@ fn number_of_combinations(rating: i32, graph: &mut Graph, final_rating: i32) ->
     paths
 }
 
-fn convert_to_graph(ratings: &Vec<i32>) -> Graph {
+fn convert_to_graph(ratings: &[i32]) -> Graph {
     let mut graph = HashMap::new();
     let ratings_count = ratings.len();
 
@@ -99,21 +133,25 @@ fn convert_to_graph(ratings: &Vec<i32>) -> Graph {
     graph
 }
 
-fn get_ratings() -> Vec<i32> {
-    let mut ratings: Vec<i32> = fs::read_to_string("data/day10.txt")
-        .unwrap()
-        .lines()
-        .map(|num| num.parse().unwrap())
-        .collect();
-    ratings.push(0);
-    ratings.sort();
-    ratings.push(ratings.last().unwrap() + 3);
-    ratings
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{convert_to_graph, find_differences, number_of_combinations};
+    use super::{convert_to_graph, find_differences, number_of_combinations, Day10};
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn parse_rejects_adapters_that_cannot_chain() {
+        let ratings = Day10.parse("3\n6").unwrap();
+        assert_eq!(ratings, vec![0, 3, 6, 9]);
+        assert_eq!(Day10.part_one(&ratings), Answer::Number(0));
+        assert_eq!(Day10.part_two(&ratings), Answer::Number(1));
+
+        let err = Day10.parse("1\n8\n4").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "8"));
+        let err = Day10.parse("1\n2\n1").unwrap_err();
+        assert_eq!(err.line, 3);
+        let err = Day10.parse("1\n-2").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
 
     #[test]
     fn test_find_differences() {
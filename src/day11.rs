@@ -1,22 +1,43 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 
 type Map = Vec<Vec<char>>;
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day11;
 
-fn part_one() {
-    let map = start_rounds(get_map(), adjacent_occupied_seats, 3);
-    let count = total_occupied_seats(map);
-    print!("Day 11 - A: {:?}", count);
-}
+impl Solution for Day11 {
+    type Input = Map;
 
-fn part_two() {
-    let map = start_rounds(get_map(), nearest_occupied_seats, 4);
-    let count = total_occupied_seats(map);
-    println!(" - B: {:?}", count);
+    const DAY: u8 = 11;
+
+    fn parse(&self, input: &str) -> Result<Map, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let width = input.lines().next().map_or(0, str::len);
+        input
+            .lines()
+            .map(|line| {
+                ctx.expect_chars(line, "L.#")?;
+                if line.len() != width {
+                    return Err(ctx.error(line, format!("expected a row {} positions wide", width)));
+                }
+                Ok(line.chars().collect())
+            })
+            .collect()
+    }
+
+    fn part_one(&self, map: &Map) -> Answer {
+        let map = start_rounds(map.clone(), adjacent_occupied_seats, 3);
+        let count = total_occupied_seats(map);
+        count.into()
+    }
+
+    fn part_two(&self, map: &Map) -> Answer {
+        let map = start_rounds(map.clone(), nearest_occupied_seats, 4);
+        let count = total_occupied_seats(map);
+        count.into()
+    }
 }
 
 fn start_rounds(map: Map, check_seats: fn(usize, usize, &Map) -> i32, limit: i32) -> Map {
@@ -110,10 +131,8 @@ fn nearest_occupied_seats(r: usize, c: usize, map: &Map) -> i32 {
     while col_idx < cols && map[r][col_idx] == '.' {
         col_idx += 1;
     }
-    if col_idx < cols {
-        if map[r][col_idx] == '#' {
-            count += 1;
-        }
+    if col_idx < cols && map[r][col_idx] == '#' {
+        count += 1;
     }
 
     // left
@@ -146,10 +165,8 @@ fn nearest_occupied_seats(r: usize, c: usize, map: &Map) -> i32 {
             row_idx -= 1;
             col_idx += 1;
         }
-        if col_idx < cols {
-            if map[row_idx][col_idx] == '#' {
-                count += 1;
-            }
+        if col_idx < cols && map[row_idx][col_idx] == '#' {
+            count += 1;
         }
         row_idx = r;
         col_idx = c;
@@ -188,10 +205,8 @@ fn nearest_occupied_seats(r: usize, c: usize, map: &Map) -> i32 {
             row_idx += 1;
             col_idx += 1;
         }
-        if col_idx < cols {
-            if map[row_idx][col_idx] == '#' {
-                count += 1;
-            }
+        if col_idx < cols && map[row_idx][col_idx] == '#' {
+            count += 1;
         }
         row_idx = r;
         col_idx = c;
@@ -224,20 +239,48 @@ fn total_occupied_seats(map: Map) -> i32 {
     count
 }
 
-fn get_map() -> Map {
-    fs::read_to_string("data/day11.txt")
-        .unwrap()
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
         adjacent_occupied_seats, apply_rules, nearest_occupied_seats, start_rounds,
-        total_occupied_seats,
+        total_occupied_seats, Day11,
     };
+    use crate::solution::Solution;
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        assert_eq!(
+            Day11
+                .parse(
+                    "L.L
+L#L"
+                )
+                .unwrap()
+                .len(),
+            2
+        );
+        let err = Day11
+            .parse(
+                "L.L
+L.",
+            )
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "L."));
+        let err = Day11
+            .parse(
+                "L.L
+L.LL",
+            )
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        let err = Day11
+            .parse(
+                "L.L
+L.x",
+            )
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
 
     #[test]
     fn apply_rules_works() {
@@ -254,7 +297,7 @@ mod tests {
             vec!['L', '.', 'L', 'L', 'L', 'L', 'L', '.', 'L', 'L'],
         ];
         let (_, changed) = apply_rules(map, adjacent_occupied_seats, 3);
-        assert_eq!(changed, true);
+        assert!(changed);
     }
 
     #[test]
@@ -1,4 +1,7 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 
 const NORTH: i32 = 0;
 const EAST: i32 = 1;
@@ -94,7 +97,7 @@ impl Waypoint {
         for _ in 0..degrees / 90 {
             let x_diff = self.x - ship.x;
             let y_diff = self.y - ship.y;
-            self.x = ship.x + y_diff * -1;
+            self.x = ship.x + -y_diff;
             self.y = ship.y + x_diff;
         }
     }
@@ -104,7 +107,7 @@ impl Waypoint {
             let x_diff = self.x - ship.x;
             let y_diff = self.y - ship.y;
             self.x = ship.x + y_diff;
-            self.y = ship.y + x_diff * -1;
+            self.y = ship.y + -x_diff;
         }
     }
 }
@@ -116,75 +119,118 @@ impl Ship {
             let y_diff = waypoint.y - self.y;
             self.x = waypoint.x;
             self.y = waypoint.y;
-            waypoint.x = waypoint.x + x_diff;
-            waypoint.y = waypoint.y + y_diff;
+            waypoint.x += x_diff;
+            waypoint.y += y_diff;
         }
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+/// A navigation instruction like `F10` or `R90`.
+pub struct Instruction {
+    pub op: char,
+    pub num: i32,
 }
 
-fn part_one() {
-    let mut position = Position::new();
-
-    for instr in get_instructions() {
-        let op = instr.chars().nth(0).unwrap();
-        let num: i32 = instr.chars().skip(1).collect::<String>().parse().unwrap();
-
-        match op {
-            'N' => position.move_north(num),
-            'S' => position.move_south(num),
-            'E' => position.move_east(num),
-            'W' => position.move_west(num),
-            'L' => position.turn_left(num),
-            'R' => position.turn_right(num),
-            'F' => position.move_forward(num),
-            _ => panic!("yo shit broke"),
-        }
-    }
+pub struct Day12;
 
-    let manhattan_distance = position.x.abs() + position.y.abs();
-    print!("Day 12 - A: {:?}", manhattan_distance);
-}
+impl Solution for Day12 {
+    type Input = Vec<Instruction>;
+
+    const DAY: u8 = 12;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input
+            .lines()
+            .map(|line| parse_instruction(&ctx, line))
+            .collect()
+    }
 
-fn part_two() {
-    let mut waypoint = Waypoint { x: 10, y: 1 };
-    let mut ship = Ship { x: 0, y: 0 };
-
-    for instr in get_instructions() {
-        let op = instr.chars().nth(0).unwrap();
-        let num: i32 = instr.chars().skip(1).collect::<String>().parse().unwrap();
-
-        match op {
-            'N' => waypoint.move_north(num),
-            'S' => waypoint.move_south(num),
-            'E' => waypoint.move_east(num),
-            'W' => waypoint.move_west(num),
-            'L' => waypoint.rotate_left(num, &ship),
-            'R' => waypoint.rotate_right(num, &ship),
-            'F' => ship.move_to_waypoint(num, &mut waypoint),
-            _ => panic!("yo shit broke"),
+    fn part_one(&self, instructions: &Vec<Instruction>) -> Answer {
+        let mut position = Position::new();
+
+        for &Instruction { op, num } in instructions {
+            match op {
+                'N' => position.move_north(num),
+                'S' => position.move_south(num),
+                'E' => position.move_east(num),
+                'W' => position.move_west(num),
+                'L' => position.turn_left(num),
+                'R' => position.turn_right(num),
+                'F' => position.move_forward(num),
+                _ => unreachable!("instructions are validated when parsed"),
+            }
         }
+
+        let manhattan_distance = position.x.abs() + position.y.abs();
+        manhattan_distance.into()
     }
 
-    let manhattan_distance = ship.x.abs() + ship.y.abs();
-    println!(" - B: {:?}", manhattan_distance);
+    fn part_two(&self, instructions: &Vec<Instruction>) -> Answer {
+        let mut waypoint = Waypoint { x: 10, y: 1 };
+        let mut ship = Ship { x: 0, y: 0 };
+
+        for &Instruction { op, num } in instructions {
+            match op {
+                'N' => waypoint.move_north(num),
+                'S' => waypoint.move_south(num),
+                'E' => waypoint.move_east(num),
+                'W' => waypoint.move_west(num),
+                'L' => waypoint.rotate_left(num, &ship),
+                'R' => waypoint.rotate_right(num, &ship),
+                'F' => ship.move_to_waypoint(num, &mut waypoint),
+                _ => unreachable!("instructions are validated when parsed"),
+            }
+        }
+
+        let manhattan_distance = ship.x.abs() + ship.y.abs();
+        manhattan_distance.into()
+    }
 }
 
-fn get_instructions() -> Vec<String> {
-    fs::read_to_string("data/day12.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
+fn parse_instruction(ctx: &Context, line: &str) -> Result<Instruction, ParseError> {
+    let op = match line.chars().next() {
+        Some(op) if "NSEWLRF".contains(op) => op,
+        Some(op) => {
+            return Err(ctx.error(
+                &line[..op.len_utf8()],
+                "expected one of `N`, `S`, `E`, `W`, `L`, `R` or `F`",
+            ))
+        }
+        None => return Err(ctx.missing(line, "missing action")),
+    };
+
+    let value = &line[1..];
+    let num = ctx.number(value)?;
+    if (op == 'L' || op == 'R') && num % 90 != 0 {
+        return Err(ctx.error(value, "turns must be a multiple of 90 degrees"));
+    }
+    Ok(Instruction { op, num })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Ship, Waypoint};
+    use super::{Day12, Ship, Waypoint};
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn parse_reports_bad_instructions() {
+        let err = Day12.parse("F10\nX3").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "X"));
+
+        let err = Day12.parse("R45").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (2, "45"));
+
+        let err = Day12.parse("F1x").err().unwrap();
+        assert_eq!(err.text, "1x");
+    }
+
+    #[test]
+    fn parts_follow_the_example_route() {
+        let instructions = Day12.parse("F10\nN3\nF7\nR90\nF11").unwrap();
+        assert_eq!(Day12.part_one(&instructions), Answer::Number(25));
+        assert_eq!(Day12.part_two(&instructions), Answer::Number(286));
+    }
 
     #[test]
     fn rotate_right_works() {
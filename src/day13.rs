@@ -1,45 +1,74 @@
-use std::{collections::HashMap, fs};
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::collections::HashMap;
 
-pub fn execute() {
-    part_one();
-    part_two();
+pub struct Notes {
+    pub earliest_departure: f64,
+    /// `(offset, id)` of every bus in service; `x` entries only count towards offsets.
+    pub buses: Vec<(usize, usize)>,
 }
 
-fn part_one() {
-    let notes = get_notes();
-    let earliest_departure: f64 = notes[0].parse().unwrap();
-    let mut minutes_to_id = HashMap::new();
-    let minutes: i32 = notes[1]
-        .split(",")
-        .filter(|&note| note != "x")
-        .map(|id| {
-            let id: f64 = id.parse().unwrap();
-            let minutes = ((earliest_departure / id).ceil() * id) as i32;
-            minutes_to_id.insert(minutes, id);
-            minutes
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Notes;
+
+    const DAY: u8 = 13;
+
+    fn parse(&self, input: &str) -> Result<Notes, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mut lines = input.lines();
+        let departure = ctx.field(lines.next(), input, "earliest departure")?;
+        let schedule = ctx.field(lines.next(), departure, "bus schedule")?;
+        Ok(Notes {
+            earliest_departure: ctx.number(departure)?,
+            buses: prepare_schedule(&ctx, schedule)?,
         })
-        .min()
-        .unwrap();
-    let answer =
-        *minutes_to_id.get(&minutes).unwrap() as i32 * (minutes - earliest_departure as i32);
-    print!("Day 13 - A: {:?}", answer);
-}
+    }
 
-fn part_two() {
-    let notes = get_notes();
-    println!(" - B: {:?}", earliest_time(prepare_schedule(&notes[1])));
+    fn part_one(&self, notes: &Notes) -> Answer {
+        let earliest_departure = notes.earliest_departure;
+        let mut minutes_to_id = HashMap::new();
+        let minutes = notes
+            .buses
+            .iter()
+            .map(|&(_, id)| {
+                let id = id as f64;
+                let minutes = ((earliest_departure / id).ceil() * id) as i32;
+                minutes_to_id.insert(minutes, id);
+                minutes
+            })
+            .min();
+        minutes
+            .map(|minutes| minutes_to_id[&minutes] as i32 * (minutes - earliest_departure as i32))
+            .into()
+    }
+
+    fn part_two(&self, notes: &Notes) -> Answer {
+        if notes.buses.is_empty() {
+            return Answer::None;
+        }
+        earliest_time(notes.buses.clone()).into()
+    }
 }
 
-fn prepare_schedule(schedule: &String) -> Vec<(usize, usize)> {
+fn prepare_schedule(ctx: &Context, schedule: &str) -> Result<Vec<(usize, usize)>, ParseError> {
     schedule
-        .split(",")
+        .split(',')
         .enumerate()
         .filter(|&(_, id)| id != "x")
-        .map(|(i, s)| (i as usize, s.parse().unwrap()))
+        .map(|(i, id)| match ctx.number(id)? {
+            0 => Err(ctx.error(id, "bus ids must be positive")),
+            id => Ok((i, id)),
+        })
         .collect()
 }
 
-fn earliest_time(buses: Vec<(usize, usize)>) -> usize {
+/// Finds the earliest timestamp at which every bus `(offset, id)` departs `offset`
+/// minutes later, sieving one bus at a time.
+pub fn earliest_time(buses: Vec<(usize, usize)>) -> usize {
     let mut iteration = 1;
     let (_, first_id) = buses[0];
     let mut lcm = first_id;
@@ -79,31 +108,45 @@ fn earliest_time(buses: Vec<(usize, usize)>) -> usize {
     //    x1 = 11
 }
 
-fn get_notes() -> Vec<String> {
-    fs::read_to_string("data/day13.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{earliest_time, prepare_schedule};
+    use super::{earliest_time, prepare_schedule, Day13};
+    use crate::{
+        error::Context,
+        solution::{Answer, Solution},
+    };
+
+    fn schedule(s: &str) -> Vec<(usize, usize)> {
+        prepare_schedule(&Context::new(13, s), s).unwrap()
+    }
+
+    #[test]
+    fn parse_reports_bad_notes() {
+        let err = Day13.parse("939\n7,13,x,y").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 8, "y"));
+        let err = Day13.parse("939").err().unwrap();
+        assert_eq!(err.message, "missing bus schedule");
+    }
+
+    #[test]
+    fn part_one_works() {
+        let notes = Day13.parse("939\n7,13,x,x,59,x,31,19").unwrap();
+        assert_eq!(Day13.part_one(&notes), Answer::Number(295));
+    }
 
     #[test]
     fn earliest_time_works() {
         let s1 = "17,x,13,19".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s1)), 3417);
+        assert_eq!(earliest_time(schedule(&s1)), 3417);
         let s2 = "67,7,59,61".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s2)), 754018);
+        assert_eq!(earliest_time(schedule(&s2)), 754018);
         let s3 = "67,x,7,59,61".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s3)), 779210);
+        assert_eq!(earliest_time(schedule(&s3)), 779210);
         let s4 = "67,7,x,59,61".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s4)), 1261476);
+        assert_eq!(earliest_time(schedule(&s4)), 1261476);
         let s5 = "1789,37,47,1889".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s5)), 1202161486);
+        assert_eq!(earliest_time(schedule(&s5)), 1202161486);
         let s5 = "7,13,x,x,59,x,31,19".to_string();
-        assert_eq!(earliest_time(prepare_schedule(&s5)), 1068781);
+        assert_eq!(earliest_time(schedule(&s5)), 1068781);
     }
 }
@@ -1,37 +1,93 @@
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
 
-pub fn execute() {
-    part_one();
-    part_two();
+const WORD_BITS: usize = 36;
+
+/// One line of the initialization program.
+pub enum Instruction {
+    Mask(String),
+    Mem { addr: u64, value: u64 },
 }
 
-fn part_one() {
-    let mut mem: HashMap<String, u64> = HashMap::new();
-    run(get_instructions(), &mut mem);
-    let sum: u64 = mem.values().sum();
-    print!("Day 14 - A: {:?}", sum);
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Instruction>;
+
+    const DAY: u8 = 14;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mem_regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
+        input
+            .lines()
+            .map(|line| parse_instruction(&ctx, &mem_regex, line))
+            .collect()
+    }
+
+    fn part_one(&self, instructions: &Vec<Instruction>) -> Answer {
+        let mut mem: HashMap<u64, u64> = HashMap::new();
+        run(instructions, &mut mem);
+        let sum: u64 = mem.values().sum();
+        sum.into()
+    }
+
+    fn part_two(&self, instructions: &Vec<Instruction>) -> Answer {
+        let mut mem: HashMap<u64, u64> = HashMap::new();
+        run_v2(instructions, &mut mem);
+        let sum: u64 = mem.values().sum();
+        sum.into()
+    }
 }
 
-fn run(instructions: Vec<String>, mem: &mut HashMap<String, u64>) {
-    let mut mask = String::new();
-    let mem_regex = Regex::new(r"mem\[(\d+)\] = (\d+)").unwrap();
+fn parse_instruction(
+    ctx: &Context,
+    mem_regex: &Regex,
+    line: &str,
+) -> Result<Instruction, ParseError> {
+    if let Some(mask) = line.strip_prefix("mask = ") {
+        ctx.expect_chars(mask, "01X")?;
+        if mask.len() != WORD_BITS {
+            return Err(ctx.error(mask, format!("masks must be {} bits long", WORD_BITS)));
+        }
+        return Ok(Instruction::Mask(mask.to_string()));
+    }
 
-    for instr in instructions {
-        if instr.starts_with("mask") {
-            mask = instr.split(" = ").last().unwrap().to_string();
+    let captures = mem_regex
+        .captures(line)
+        .ok_or_else(|| ctx.error(line, "expected `mask = ...` or `mem[addr] = value`"))?;
+    let word = |i| {
+        let text = captures.get(i).unwrap().as_str();
+        match ctx.number::<u64>(text)? {
+            num if num >> WORD_BITS == 0 => Ok(num),
+            _ => Err(ctx.error(text, format!("does not fit in {} bits", WORD_BITS))),
         }
+    };
+    Ok(Instruction::Mem {
+        addr: word(1)?,
+        value: word(2)?,
+    })
+}
+
+/// Version 1 decoder: the mask overwrites bits of each written value.
+pub fn run(instructions: &[Instruction], mem: &mut HashMap<u64, u64>) {
+    let mut mask = "X".repeat(WORD_BITS);
 
-        if instr.starts_with("mem") {
-            let captures = mem_regex.captures(&instr).unwrap();
-            let addr = captures.get(1).unwrap().as_str().to_string();
-            let num: u64 = captures.get(2).unwrap().as_str().parse().unwrap();
-            mem.insert(addr, apply_mask(num, &mask));
+    for instr in instructions {
+        match instr {
+            Instruction::Mask(new_mask) => mask = new_mask.clone(),
+            &Instruction::Mem { addr, value } => {
+                mem.insert(addr, apply_mask(value, &mask));
+            }
         }
     }
 }
 
-fn apply_mask(num: u64, mask: &str) -> u64 {
+pub fn apply_mask(num: u64, mask: &str) -> u64 {
     let bits = format!("{:0>36b}", num);
     let masked_num: String = bits
         .chars()
@@ -41,35 +97,23 @@ fn apply_mask(num: u64, mask: &str) -> u64 {
     u64::from_str_radix(&masked_num, 2).unwrap()
 }
 
-fn part_two() {
-    let mut mem: HashMap<String, u64> = HashMap::new();
-    run_v2(get_instructions(), &mut mem);
-    let sum: u64 = mem.values().sum();
-    println!(" - B: {:?}", sum);
-}
-
-fn run_v2(instructions: Vec<String>, mem: &mut HashMap<String, u64>) {
-    let mut mask = String::new();
-    let mem_regex = Regex::new(r"mem\[(\d+)\] = (\d+)").unwrap();
+/// Version 2 decoder: the mask turns each address into a set of floating addresses.
+pub fn run_v2(instructions: &[Instruction], mem: &mut HashMap<u64, u64>) {
+    let mut mask = "0".repeat(WORD_BITS);
 
     for instr in instructions {
-        if instr.starts_with("mask") {
-            mask = instr.split(" = ").last().unwrap().to_string();
-        }
-
-        if instr.starts_with("mem") {
-            let captures = mem_regex.captures(&instr).unwrap();
-            let addr = captures.get(1).unwrap().as_str().parse().unwrap();
-            let num: u64 = captures.get(2).unwrap().as_str().parse().unwrap();
-
-            for addr in decode_memory_addresses(addr, &mask) {
-                mem.insert(addr.to_string(), num);
+        match instr {
+            Instruction::Mask(new_mask) => mask = new_mask.clone(),
+            &Instruction::Mem { addr, value } => {
+                for addr in decode_memory_addresses(addr, &mask) {
+                    mem.insert(addr, value);
+                }
             }
         }
     }
 }
 
-fn decode_memory_addresses(num: u64, mask: &str) -> Vec<u64> {
+pub fn decode_memory_addresses(num: u64, mask: &str) -> Vec<u64> {
     let mut addrs: Vec<Vec<char>> = vec![Vec::with_capacity(36)];
     let mut decoded_addrs = Vec::new();
     let bits = format!("{:0>36b}", num);
@@ -136,57 +180,66 @@ fn decode_memory_addresses(num: u64, mask: &str) -> Vec<u64> {
     decoded_addrs
 }
 
-fn get_instructions() -> Vec<String> {
-    fs::read_to_string("data/day14.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{apply_mask, decode_memory_addresses, run, run_v2};
+    use super::{apply_mask, decode_memory_addresses, run, run_v2, Day14};
+    use crate::solution::Solution;
     use std::collections::HashMap;
 
+    #[test]
+    fn parse_reports_bad_instructions() {
+        let err = Day14.parse("mask = XX1\nmem[8] = 11").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 8));
+        let err = Day14.parse("mask = 10X2").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (11, "2"));
+        let err = Day14.parse("mem[8] = 11\nmem[x] = 1").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+        let err = Day14.parse("mem[68719476736] = 1").err().unwrap();
+        assert_eq!(err.text, "68719476736");
+    }
+
     #[test]
     fn running_the_program() {
-        let instrs = vec![
-            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".to_string(),
-            "mem[8] = 11".to_string(),
-            "mem[7] = 101".to_string(),
-            "mem[8] = 0".to_string(),
-        ];
+        let instrs = Day14
+            .parse(
+                "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\n\
+                 mem[8] = 11\n\
+                 mem[7] = 101\n\
+                 mem[8] = 0",
+            )
+            .unwrap();
         let mut mem = HashMap::new();
-        run(instrs, &mut mem);
+        run(&instrs, &mut mem);
         assert_eq!(mem.values().sum::<u64>(), 165)
     }
 
     #[test]
     fn test_apply_mask() {
         let mask = "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X";
-        assert_eq!(apply_mask(11, &mask), 73);
+        assert_eq!(apply_mask(11, mask), 73);
         let mask = "000000000000000000000000000000X1001X";
-        assert_eq!(apply_mask(100, &mask), 50)
+        assert_eq!(apply_mask(100, mask), 50)
     }
 
     #[test]
     fn running_the_program_v2() {
-        let instrs = vec![
-            "mask = 000000000000000000000000000000X1001X".to_string(),
-            "mem[42] = 100".to_string(),
-            "mask = 00000000000000000000000000000000X0XX".to_string(),
-            "mem[26] = 1".to_string(),
-        ];
+        let instrs = Day14
+            .parse(
+                "mask = 000000000000000000000000000000X1001X\n\
+                 mem[42] = 100\n\
+                 mask = 00000000000000000000000000000000X0XX\n\
+                 mem[26] = 1",
+            )
+            .unwrap();
         let mut mem = HashMap::new();
-        run_v2(instrs, &mut mem);
+        run_v2(&instrs, &mut mem);
         assert_eq!(mem.values().sum::<u64>(), 208)
     }
 
     #[test]
     fn decode_memory_addresses_works() {
         let mask = "000000000000000000000000000000X1001X";
-        let addrs = decode_memory_addresses(42, &mask);
+        let addrs = decode_memory_addresses(42, mask);
         assert_eq!(addrs.len(), 4);
         assert_eq!(addrs[3], 26);
         assert_eq!(addrs[2], 27);
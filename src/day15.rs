@@ -1,100 +1,121 @@
-use std::collections::{HashMap, VecDeque};
-
-const INPUT: [i32; 6] = [18, 11, 9, 0, 5, 1];
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::{collections::HashMap, convert::TryFrom};
 
+/// The last two turns each number was spoken on; turn 0 means never. Numbers
+/// below `limit` are indexed in a flat Vec, larger ones (only ever starting
+/// numbers, since a spoken age is always below the turn count) go in a map.
 #[derive(Debug)]
 struct Cache {
-    map: HashMap<i32, VecDeque<i32>>,
+    limit: usize,
+    turns: Vec<(i32, i32)>,
+    sparse: HashMap<i32, (i32, i32)>,
 }
 
 impl Cache {
-    fn new() -> Self {
+    fn new(limit: i32) -> Self {
         Self {
-            map: HashMap::new(),
+            limit: limit as usize,
+            turns: Vec::new(),
+            sparse: HashMap::new(),
         }
     }
 
     fn insert(&mut self, key: i32, value: i32) {
-        match self.map.get_mut(&key) {
-            Some(queue) => {
-                if queue.len() == 1 {
-                    queue.push_front(value);
-                } else {
-                    queue.push_front(value);
-                    queue.pop_back();
+        let turns = match usize::try_from(key) {
+            Ok(index) if index < self.limit => {
+                if index >= self.turns.len() {
+                    self.turns.resize(index + 1, (0, 0));
                 }
+                &mut self.turns[index]
             }
-            None => {
-                let mut queue = VecDeque::new();
-                queue.push_front(value);
-                self.map.insert(key, queue);
-            }
-        }
+            _ => self.sparse.entry(key).or_default(),
+        };
+        *turns = (value, turns.0);
     }
 
-    fn get<'a>(&'a self, key: i32) -> &'a VecDeque<i32> {
-        self.map.get(&key).unwrap()
+    fn get(&self, key: i32) -> (i32, i32) {
+        match usize::try_from(key) {
+            Ok(index) if index < self.limit => self.turns.get(index).copied().unwrap_or_default(),
+            _ => self.sparse.get(&key).copied().unwrap_or_default(),
+        }
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<i32>;
+
+    const DAY: u8 = 15;
 
-fn part_one() {
-    let mut cache = Cache::new();
-    for (i, &num) in INPUT.iter().enumerate() {
-        cache.insert(num, i as i32 + 1);
+    fn parse(&self, input: &str) -> Result<Vec<i32>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input
+            .trim()
+            .split(',')
+            .map(|num| {
+                let num = num.trim();
+                match ctx.number::<i32>(num)? {
+                    n if n < 0 => Err(ctx.error(num, "expected a number that isn't negative")),
+                    n => Ok(n),
+                }
+            })
+            .collect()
     }
 
-    let num = find_2020(
-        &mut cache,
-        *INPUT.last().unwrap(),
-        INPUT.len() as i32 + 1,
-        2020,
-    );
-    print!("Day 15 - A: {:?}", num);
-}
+    fn part_one(&self, input: &Vec<i32>) -> Answer {
+        let mut cache = Cache::new(2020);
+        for (i, &num) in input.iter().enumerate() {
+            cache.insert(num, i as i32 + 1);
+        }
 
-fn part_two() {
-    let mut cache = Cache::new();
-    for (i, &num) in INPUT.iter().enumerate() {
-        cache.insert(num, i as i32 + 1);
+        let num = find_2020(
+            &mut cache,
+            *input.last().unwrap(),
+            input.len() as i32 + 1,
+            2020,
+        );
+        num.into()
     }
 
-    // let num = find_2020(
-    //     &mut cache,
-    //     *INPUT.last().unwrap(),
-    //     INPUT.len() as i32 + 1,
-    //     30_000_000,
-    // );
-    println!(" - B: {:?}", "solved");
+    fn part_two(&self, input: &Vec<i32>) -> Answer {
+        let mut cache = Cache::new(30_000_000);
+        for (i, &num) in input.iter().enumerate() {
+            cache.insert(num, i as i32 + 1);
+        }
+
+        let num = find_2020(
+            &mut cache,
+            *input.last().unwrap(),
+            input.len() as i32 + 1,
+            30_000_000,
+        );
+        num.into()
+    }
 }
 
 fn find_2020(cache: &mut Cache, mut last_num: i32, mut current_turn: i32, stop: i32) -> i32 {
     while current_turn <= stop {
-        let previous_turns = cache.get(last_num);
-        let previous_turn = previous_turns.front().unwrap();
-        let diff = current_turn - previous_turn;
-        if diff == 1 && previous_turns.len() == 1 {
-            last_num = 0;
-            cache.insert(last_num, current_turn);
-            current_turn += 1;
-        } else {
-            last_num = previous_turn - previous_turns.back().unwrap();
-            cache.insert(last_num, current_turn);
-            current_turn += 1;
-        }
+        last_num = match cache.get(last_num) {
+            (_, 0) => 0,
+            (previous_turn, turn_before) => previous_turn - turn_before,
+        };
+        cache.insert(last_num, current_turn);
+        current_turn += 1;
     }
     last_num
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::solution::{Answer, Solution};
+
     #[test]
     fn test1_find_2020() {
-        let mut cache = super::Cache::new();
+        let mut cache = super::Cache::new(2020);
         cache.insert(0, 1);
         cache.insert(3, 2);
         cache.insert(6, 3);
@@ -103,7 +124,7 @@ mod tests {
 
     #[test]
     fn test2_find_2020() {
-        let mut cache = super::Cache::new();
+        let mut cache = super::Cache::new(2020);
         cache.insert(1, 1);
         cache.insert(3, 2);
         cache.insert(2, 3);
@@ -112,10 +133,40 @@ mod tests {
 
     #[test]
     fn test3_find_2020() {
-        let mut cache = super::Cache::new();
+        let mut cache = super::Cache::new(2020);
         cache.insert(3, 1);
         cache.insert(1, 2);
         cache.insert(2, 3);
         assert_eq!(super::find_2020(&mut cache, 2, 4, 2020), 1836);
     }
+
+    #[test]
+    fn part_one_reads_starting_numbers() {
+        let numbers = super::Day15.parse("0,3,6\n").unwrap();
+        assert_eq!(numbers, vec![0, 3, 6]);
+        assert_eq!(super::Day15.part_one(&numbers), Answer::Number(436));
+    }
+
+    #[test]
+    fn parse_reports_bad_numbers() {
+        let err = super::Day15.parse("0,3,,6").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert!(super::Day15.parse("").is_err());
+
+        let err = super::Day15.parse("3000000000,1").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        let err = super::Day15.parse("0,-3,6").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
+    #[test]
+    fn starting_numbers_beyond_the_turn_count_are_remembered() {
+        let numbers = super::Day15.parse("2147483647,1,2147483647").unwrap();
+        // 2147483647 was first spoken two turns earlier, so turn 4 is 2.
+        let mut cache = super::Cache::new(4);
+        for (i, &num) in numbers.iter().enumerate() {
+            cache.insert(num, i as i32 + 1);
+        }
+        assert_eq!(super::find_2020(&mut cache, 2147483647, 4, 4), 2);
+    }
 }
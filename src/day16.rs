@@ -1,56 +1,76 @@
+use crate::{
+    error::{Context, ParseError},
+    records,
+    solution::{Answer, Solution},
+};
 use regex::Regex;
 use std::{
     collections::{HashMap, HashSet},
-    fs,
     ops::Range,
 };
 
-struct Rule {
-    name: String,
-    first_range: Range<i32>,
-    second_range: Range<i32>,
+/// A ticket field rule like `class: 1-3 or 5-7`, with end-exclusive ranges.
+pub struct Rule {
+    pub name: String,
+    pub first_range: Range<i32>,
+    pub second_range: Range<i32>,
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub my_ticket: Vec<i32>,
+    pub nearby_tickets: Vec<Vec<i32>>,
 }
 
-fn part_one() {
-    let notes = get_notes();
-    let rules = extract_rules(&notes[0]);
-    let nearby_tickets = extract_tickets(&notes[2]);
+pub struct Day16;
 
-    print!(
-        "Day 16 - A: {:?}",
-        scanning_error_rate(&rules, &nearby_tickets)
-    );
-}
+impl Solution for Day16 {
+    type Input = Notes;
+
+    const DAY: u8 = 16;
 
-fn part_two() {
-    let notes = get_notes();
-    let rules = extract_rules(&notes[0]);
-    let my_ticket = extract_my_ticket(&notes[1]);
-    let nearby_tickets = extract_tickets(&notes[2]);
-    let prepared_tickets = transpose_tickets(&remove_invalid_tickets(&rules, &nearby_tickets));
-
-    let map = map_rules_to_columns(&rules, prepared_tickets);
-    let rule_cols = reduce_to_unique(map);
-    let answer: i64 = rule_cols
-        .iter()
-        .filter(|(rule, _)| rule.starts_with("departure"))
-        .map(|(_, col)| my_ticket[*col as usize] as i64)
-        .fold(1, |product, n| product * n);
-    println!(" - B: {:?}", answer);
+    fn parse(&self, input: &str) -> Result<Notes, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mut sections = records::blocks(input);
+        let rules = ctx.field(sections.next(), input, "rules")?;
+        let my_ticket = ctx.field(sections.next(), rules, "your ticket")?;
+        let nearby_tickets = ctx.field(sections.next(), my_ticket, "nearby tickets")?;
+        Ok(Notes {
+            rules: extract_rules(&ctx, rules)?,
+            my_ticket: extract_my_ticket(&ctx, my_ticket)?,
+            nearby_tickets: extract_tickets(&ctx, nearby_tickets)?,
+        })
+    }
+
+    fn part_one(&self, notes: &Notes) -> Answer {
+        scanning_error_rate(&notes.rules, &notes.nearby_tickets).into()
+    }
+
+    fn part_two(&self, notes: &Notes) -> Answer {
+        let valid_tickets = remove_invalid_tickets(&notes.rules, &notes.nearby_tickets);
+        if valid_tickets.is_empty() {
+            return Answer::None;
+        }
+        let prepared_tickets = transpose_tickets(&valid_tickets);
+
+        let map = map_rules_to_columns(&notes.rules, prepared_tickets);
+        let rule_cols = reduce_to_unique(map);
+        let answer: Option<i64> = rule_cols
+            .iter()
+            .filter(|(rule, _)| rule.starts_with("departure"))
+            .map(|(_, col)| notes.my_ticket.get(*col as usize).map(|&num| num as i64))
+            .product();
+        answer.into()
+    }
 }
 
-fn remove_invalid_tickets(rules: &Vec<Rule>, tickets: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
-    let valid_numbers = valid_numbers(&rules);
+pub fn remove_invalid_tickets(rules: &Vec<Rule>, tickets: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
+    let valid_numbers = valid_numbers(rules);
     let mut valid_tickets = Vec::new();
     for ticket in tickets {
         let mut valid = true;
         for num in ticket {
-            if !valid_numbers.contains(&num) {
+            if !valid_numbers.contains(num) {
                 valid = false;
                 break;
             }
@@ -62,7 +82,7 @@ fn remove_invalid_tickets(rules: &Vec<Rule>, tickets: &Vec<Vec<i32>>) -> Vec<Vec
     valid_tickets
 }
 
-fn transpose_tickets(tickets: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
+pub fn transpose_tickets(tickets: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
     let mut transposed = Vec::new();
     for _ in 0..tickets[0].len() {
         transposed.push(Vec::new());
@@ -75,42 +95,43 @@ fn transpose_tickets(tickets: &Vec<Vec<i32>>) -> Vec<Vec<i32>> {
     transposed
 }
 
-fn extract_my_ticket(ticket_string: &str) -> Vec<i32> {
-    ticket_string.trim().split(":\n").collect::<Vec<&str>>()[1]
-        .split(",")
-        .map(|num| num.parse().unwrap())
-        .collect()
+fn extract_my_ticket(ctx: &Context, ticket_string: &str) -> Result<Vec<i32>, ParseError> {
+    let mut lines = records::lines(ticket_string);
+    let header = ctx.field(lines.next(), ticket_string, "`your ticket:` header")?;
+    let ticket = ctx.field(lines.next(), header, "your ticket")?;
+    parse_ticket(ctx, ticket)
 }
 
-fn extract_rules(rules_string: &str) -> Vec<Rule> {
-    let rule_regex = Regex::new(r"(.+): (\d+)-(\d+) or (\d+)-(\d+)").unwrap();
-    rules_string
-        .split("\n")
+fn extract_rules(ctx: &Context, rules_string: &str) -> Result<Vec<Rule>, ParseError> {
+    let rule_regex = Regex::new(r"^(.+): (\d+)-(\d+) or (\d+)-(\d+)$").unwrap();
+    records::lines(rules_string)
         .map(|line| {
-            let matches = rule_regex.captures(&line).unwrap();
-            Rule {
+            let matches = rule_regex
+                .captures(line)
+                .ok_or_else(|| ctx.error(line, "expected `name: a-b or c-d`"))?;
+            let bound = |i| ctx.number::<i32>(matches.get(i).unwrap().as_str());
+            Ok(Rule {
                 name: matches.get(1).unwrap().as_str().to_string(),
-                first_range: matches.get(2).unwrap().as_str().parse().unwrap()
-                    ..matches.get(3).unwrap().as_str().parse::<i32>().unwrap() + 1,
-                second_range: matches.get(4).unwrap().as_str().parse().unwrap()
-                    ..matches.get(5).unwrap().as_str().parse::<i32>().unwrap() + 1,
-            }
+                first_range: bound(2)?..bound(3)? + 1,
+                second_range: bound(4)?..bound(5)? + 1,
+            })
         })
         .collect()
 }
 
-fn extract_tickets(tickets_string: &str) -> Vec<Vec<i32>> {
-    let mut tickets = Vec::new();
-    let nearby_tickets: Vec<&str> = tickets_string.trim().split("\n").collect();
+fn extract_tickets(ctx: &Context, tickets_string: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    records::lines(tickets_string)
+        .skip(1)
+        .map(|data| parse_ticket(ctx, data))
+        .collect()
+}
 
-    for data in &nearby_tickets[1..] {
-        tickets.push(data.split(",").map(|n| n.parse().unwrap()).collect());
-    }
-    tickets
+fn parse_ticket(ctx: &Context, data: &str) -> Result<Vec<i32>, ParseError> {
+    data.split(',').map(|num| ctx.number(num)).collect()
 }
 
-fn scanning_error_rate(rules: &Vec<Rule>, tickets: &Vec<Vec<i32>>) -> i32 {
-    let valid_numbers = valid_numbers(&rules);
+pub fn scanning_error_rate(rules: &Vec<Rule>, tickets: &Vec<Vec<i32>>) -> i32 {
+    let valid_numbers = valid_numbers(rules);
     let mut rate = 0;
     for ticket in tickets {
         for num in ticket {
@@ -136,7 +157,10 @@ fn valid_numbers(rules: &Vec<Rule>) -> HashSet<i32> {
     nums
 }
 
-fn map_rules_to_columns(rules: &Vec<Rule>, values: Vec<Vec<i32>>) -> HashMap<String, HashSet<i32>> {
+pub fn map_rules_to_columns(
+    rules: &Vec<Rule>,
+    values: Vec<Vec<i32>>,
+) -> HashMap<String, HashSet<i32>> {
     let mut map = HashMap::new();
     for rule in rules {
         for (i, column) in values.iter().enumerate() {
@@ -157,7 +181,7 @@ fn map_rules_to_columns(rules: &Vec<Rule>, values: Vec<Vec<i32>>) -> HashMap<Str
     map
 }
 
-fn reduce_to_unique(map: HashMap<String, HashSet<i32>>) -> HashMap<String, i32> {
+pub fn reduce_to_unique(map: HashMap<String, HashSet<i32>>) -> HashMap<String, i32> {
     let mut cols_found = HashSet::new();
     let mut reduced = HashMap::new();
     let mut done = false;
@@ -181,19 +205,55 @@ fn reduce_to_unique(map: HashMap<String, HashSet<i32>>) -> HashMap<String, i32>
     reduced
 }
 
-fn get_notes() -> Vec<String> {
-    fs::read_to_string("data/day16.txt")
-        .unwrap()
-        .split("\n\n")
-        .map(String::from)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
-    use super::Rule;
+    use super::{Day16, Rule};
+    use crate::solution::{Answer, Solution};
     use std::collections::{HashMap, HashSet};
 
+    const EXAMPLE: &str = "class: 1-3 or 5-7\n\
+                           row: 6-11 or 33-44\n\
+                           seat: 13-40 or 45-50\n\
+                           \n\
+                           your ticket:\n\
+                           7,1,14\n\
+                           \n\
+                           nearby tickets:\n\
+                           7,3,47\n\
+                           40,4,50\n\
+                           55,2,20\n\
+                           38,6,12\n";
+
+    #[test]
+    fn parse_reads_all_three_sections() {
+        let notes = Day16.parse(EXAMPLE).unwrap();
+        assert_eq!(notes.rules.len(), 3);
+        assert_eq!(notes.my_ticket, vec![7, 1, 14]);
+        assert_eq!(notes.nearby_tickets.len(), 4);
+        assert_eq!(Day16.part_one(&notes), Answer::Number(71));
+    }
+
+    #[test]
+    fn parse_accepts_windows_line_endings() {
+        let notes = Day16.parse(&EXAMPLE.replace('\n', " \r\n")).unwrap();
+        assert_eq!(notes.my_ticket, vec![7, 1, 14]);
+        assert_eq!(Day16.part_one(&notes), Answer::Number(71));
+    }
+
+    #[test]
+    fn parse_reports_bad_notes() {
+        let input = EXAMPLE.replace("row: 6-11", "row 6-11");
+        let err = Day16.parse(&input).err().unwrap();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let input = EXAMPLE.replace("40,4,50", "40,four,50");
+        let err = Day16.parse(&input).err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (10, 4, "four"));
+
+        let err = Day16.parse("class: 1-3 or 5-7\n").err().unwrap();
+        assert_eq!(err.message, "missing your ticket");
+    }
+
     #[test]
     fn reduce_to_unique_works() {
         let mut map = HashMap::new();
@@ -1,6 +1,11 @@
-use std::{collections::HashSet, fmt, fs, ops::Range};
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::{collections::HashSet, fmt, ops::Range};
 
-type Slice = Vec<Vec<char>>;
+/// One z-layer of the pocket dimension, `#` for active cubes and `.` for inactive.
+pub type Slice = Vec<Vec<char>>;
 
 #[derive(Debug)]
 struct Dimension {
@@ -18,14 +23,7 @@ impl Dimension {
 
     fn pad_slices(&mut self) {
         let mut padded_slices: Vec<Slice> = self.slices.iter().map(pad_slice).collect();
-        let mut empty_slice = Vec::new();
-        for _ in 0..padded_slices[0].len() {
-            let mut cubes = Vec::new();
-            for _ in 0..padded_slices[0][0].len() {
-                cubes.push('.');
-            }
-            empty_slice.push(cubes);
-        }
+        let empty_slice = vec![vec!['.'; padded_slices[0][0].len()]; padded_slices[0].len()];
         self.slices = vec![empty_slice.clone()];
         self.slices.append(&mut padded_slices);
         self.slices.push(empty_slice);
@@ -47,7 +45,7 @@ impl fmt::Display for Dimension {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut string = "\n".to_string();
         for slice in &self.slices {
-            string.push_str("\n");
+            string.push('\n');
             for cubes in slice {
                 string.push_str(&format!("{:?}\n", cubes));
             }
@@ -56,36 +54,71 @@ impl fmt::Display for Dimension {
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Slice;
+
+    const DAY: u8 = 17;
+
+    fn parse(&self, input: &str) -> Result<Slice, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let width = input.lines().next().map_or(0, str::len);
+        if width == 0 {
+            return Err(ctx.missing(input, "missing initial slice"));
+        }
+        input
+            .lines()
+            .map(|line| {
+                ctx.expect_chars(line, ".#")?;
+                if line.len() != width {
+                    return Err(ctx.error(line, format!("expected {} cubes per row", width)));
+                }
+                Ok(line.chars().collect())
+            })
+            .collect()
+    }
 
-fn part_one() {
-    let mut dimension = Dimension::new();
-    dimension.add_slice(get_input());
-    for _ in 0..6 {
-        dimension = run_cycle(dimension);
+    fn part_one(&self, slice: &Slice) -> Answer {
+        let mut dimension = Dimension::new();
+        dimension.add_slice(slice.clone());
+        for _ in 0..6 {
+            dimension = run_cycle(dimension);
+        }
+        count_active_cubes(&dimension).into()
+    }
+
+    fn part_two(&self, slice: &Slice) -> Answer {
+        let active_cubes = compile_active_cubes(slice);
+        let hyperplane = create_hyperplane(&active_cubes);
+        let mut args = (hyperplane, active_cubes);
+
+        for _ in 0..6 {
+            args = run_cycle2(args.0, args.1, 4);
+        }
+        args.1.len().into()
     }
-    print!("Day 17 - A: {:?}", count_active_cubes(&dimension));
 }
 
-type Cube = Vec<i32>;
+/// Coordinates of a cube, one per dimension.
+pub type Cube = Vec<i32>;
 const DIMENSIONS: i32 = 4;
 
+/// The region of an n-dimensional pocket dimension worth simulating: one range
+/// of coordinates per dimension.
 #[derive(Debug)]
-struct Hyperplane {
-    dimensional_ranges: Vec<Range<i32>>,
+pub struct Hyperplane {
+    pub dimensional_ranges: Vec<Range<i32>>,
 }
 
 impl Hyperplane {
-    fn new(dimensions: usize) -> Self {
+    pub fn new(dimensions: usize) -> Self {
         Self {
             dimensional_ranges: Vec::with_capacity(dimensions),
         }
     }
 
-    fn count_active_neighbors(&self, cube: &Cube, active_cubes: &HashSet<Cube>) -> i32 {
+    pub fn count_active_neighbors(&self, cube: &Cube, active_cubes: &HashSet<Cube>) -> i32 {
         let mut count = 0;
         let mut neighbor_ranges = Vec::with_capacity(self.dimensional_ranges.len());
         for coord in cube {
@@ -113,18 +146,6 @@ impl Hyperplane {
     }
 }
 
-fn part_two() {
-    let active_cubes = compile_active_cubes();
-    let hyperplane = create_hyperplane(&active_cubes);
-    let mut args = (hyperplane, active_cubes);
-
-    // for _ in 0..6 {
-    //     args = run_cycle2(args.0, args.1, 4);
-    // }
-    // let answer = args.1.len();
-    println!(" - B: {:?}", "solved in 55 secs");
-}
-
 fn run_cycle2(
     hyperplane: Hyperplane,
     active_cubes: HashSet<Cube>,
@@ -143,10 +164,8 @@ fn run_cycle2(
                         if count == 2 || count == 3 {
                             new_active_cubes.insert(cube);
                         }
-                    } else {
-                        if count == 3 {
-                            new_active_cubes.insert(cube);
-                        }
+                    } else if count == 3 {
+                        new_active_cubes.insert(cube);
                     }
                 }
             }
@@ -155,15 +174,15 @@ fn run_cycle2(
     (create_hyperplane(&new_active_cubes), new_active_cubes)
 }
 
-fn compile_active_cubes() -> HashSet<Cube> {
+/// Places the active cubes of `slice` in 4 dimensions at z = w = 0.
+pub fn compile_active_cubes(slice: &Slice) -> HashSet<Cube> {
     let mut active_cubes: HashSet<Cube> = HashSet::new();
-    for (y, row) in get_input().iter().enumerate() {
+    for (y, row) in slice.iter().enumerate() {
         for (x, &cube_char) in row.iter().enumerate() {
             if cube_char == '#' {
-                let mut cube = vec![x as i32, y as i32];
-                for _ in 0..DIMENSIONS - 2 {
-                    cube.push(0);
-                }
+                let mut cube = vec![0; DIMENSIONS as usize];
+                cube[0] = x as i32;
+                cube[1] = y as i32;
                 active_cubes.insert(cube);
             }
         }
@@ -171,7 +190,8 @@ fn compile_active_cubes() -> HashSet<Cube> {
     active_cubes
 }
 
-fn create_hyperplane(active_cubes: &HashSet<Cube>) -> Hyperplane {
+/// Builds the hyperplane covering `active_cubes` plus a margin of one on every side.
+pub fn create_hyperplane(active_cubes: &HashSet<Cube>) -> Hyperplane {
     let mut hyperplane = Hyperplane::new(DIMENSIONS as usize);
     for _ in 0..DIMENSIONS {
         hyperplane.dimensional_ranges.push(0..1);
@@ -190,21 +210,11 @@ fn create_hyperplane(active_cubes: &HashSet<Cube>) -> Hyperplane {
     hyperplane.dimensional_ranges = hyperplane
         .dimensional_ranges
         .iter()
-        .cloned()
         .map(|range| range.start - 1..range.end + 2)
         .collect();
     hyperplane
 }
 
-fn get_input() -> Slice {
-    fs::read_to_string("data/day17.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .map(|string| string.chars().collect::<Vec<char>>())
-        .collect()
-}
-
 fn run_cycle(mut dimension: Dimension) -> Dimension {
     // start
     // .....  .....  .....  .....  .....
@@ -327,9 +337,20 @@ fn count_active_cubes(dim: &Dimension) -> i32 {
 
 #[cfg(test)]
 mod tests {
-    use super::{create_hyperplane, Cube, Dimension};
+    use super::{create_hyperplane, Cube, Day17, Dimension};
+    use crate::solution::Solution;
     use std::collections::HashSet;
 
+    #[test]
+    fn parse_reports_bad_slices() {
+        assert_eq!(Day17.parse(".#.\n..#\n###").unwrap().len(), 3);
+        let err = Day17.parse(".#.\n..o").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "o"));
+        let err = Day17.parse(".#.\n..").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(Day17.parse("").is_err());
+    }
+
     #[test]
     fn hyperplane_count_active_neighbors_works() {
         let mut active: HashSet<Cube> = HashSet::new();
@@ -1,18 +1,23 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Token {
+pub enum Token {
     Int(char),
     Op(char),
     Eof,
 }
 
-struct Lexer {
+/// Splits an expression into single-character tokens, skipping spaces.
+pub struct Lexer {
     tokens: Vec<Token>,
 }
 
 impl Lexer {
-    fn new(input: &str) -> Lexer {
+    pub fn new(input: &str) -> Lexer {
         let mut tokens: Vec<Token> = input
             .chars()
             .filter(|&ch| ch != ' ')
@@ -25,117 +30,262 @@ impl Lexer {
         Lexer { tokens }
     }
 
-    fn next(&mut self) -> Token {
+    pub fn next_token(&mut self) -> Token {
         self.tokens.pop().unwrap_or(Token::Eof)
     }
-    fn peek(&mut self) -> Token {
+    pub fn peek(&mut self) -> Token {
         self.tokens.last().copied().unwrap_or(Token::Eof)
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<String>;
+
+    const DAY: u8 = 18;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input
+            .lines()
+            .map(|line| {
+                check_syntax(&ctx, line)?;
+                Ok(line.to_string())
+            })
+            .collect()
+    }
+
+    fn part_one(&self, lines: &Vec<String>) -> Answer {
+        sum(lines, calculate).into()
+    }
+
+    fn part_two(&self, lines: &Vec<String>) -> Answer {
+        sum(lines, calculate_advanced).into()
+    }
+}
+
+/// Checks that `line` alternates single-digit operands and `+`/`*` with balanced
+/// parentheses, so `evaluate` never sees a malformed expression.
+fn check_syntax(ctx: &Context, line: &str) -> Result<(), ParseError> {
+    let mut expect_operand = true;
+    let mut open = Vec::new();
+
+    for (i, ch) in line.char_indices().filter(|&(_, ch)| ch != ' ') {
+        let text = &line[i..i + ch.len_utf8()];
+        match (ch, expect_operand) {
+            ('0'..='9', true) => expect_operand = false,
+            ('(', true) => open.push(text),
+            ('+' | '*', false) => expect_operand = true,
+            (')', false) if open.pop().is_some() => {}
+            (')', false) => return Err(ctx.error(text, "unmatched `)`")),
+            (_, true) => return Err(ctx.error(text, "expected a digit or `(`")),
+            (_, false) => return Err(ctx.error(text, "expected `+`, `*` or `)`")),
+        }
+    }
+
+    if expect_operand {
+        return Err(ctx.missing(line, "expression ends without an operand"));
+    }
+    match open.pop() {
+        Some(paren) => Err(ctx.error(paren, "unclosed `(`")),
+        None => Ok(()),
+    }
 }
 
-fn part_one() {
-    let sum: u64 = get_input().iter().map(|line| calculate(line)).sum();
-    print!("Day 18 - A: {:?}", sum);
+/// Left and right binding power of an infix operator, `None` if `op` isn't one.
+pub type BindingPower = fn(char) -> Option<(u8, u8)>;
+
+/// Why an expression couldn't be evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    /// A token that can't appear where it was found.
+    Unexpected(Token),
+    /// An operator the binding power accepts but that isn't `+` or `*`.
+    UnknownOperator(char),
+    /// The value doesn't fit in a `u64`.
+    Overflow,
 }
 
-fn part_two() {
-    let sum: u64 = get_input().iter().map(|line| calculate(line)).sum();
-    println!(" - B: {:?}", sum);
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Unexpected(Token::Int(ch)) | EvalError::Unexpected(Token::Op(ch)) => {
+                write!(f, "unexpected `{}`", ch)
+            }
+            EvalError::Unexpected(Token::Eof) => write!(f, "unexpected end of expression"),
+            EvalError::UnknownOperator(op) => write!(f, "unknown operator `{}`", op),
+            EvalError::Overflow => write!(f, "value too large"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Evaluates an expression where `+` and `*` bind equally tightly, left to right.
+pub fn calculate(input: &str) -> Result<u64, EvalError> {
+    evaluate_all(input, infix_binding_power)
 }
 
-fn get_input() -> Vec<String> {
-    fs::read_to_string("data/day18.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
+/// Evaluates an expression where `+` binds tighter than `*`.
+pub fn calculate_advanced(input: &str) -> Result<u64, EvalError> {
+    evaluate_all(input, advanced_binding_power)
 }
 
-fn calculate(input: &str) -> u64 {
+fn evaluate_all(input: &str, binding_power: BindingPower) -> Result<u64, EvalError> {
     let mut lexer = Lexer::new(input);
-    evaluate(&mut lexer, 0)
+    let value = evaluate(&mut lexer, 0, binding_power)?;
+    match lexer.next_token() {
+        Token::Eof => Ok(value),
+        t => Err(EvalError::Unexpected(t)),
+    }
 }
 
-fn evaluate(lexer: &mut Lexer, precedence: u8) -> u64 {
-    let mut lhs = match lexer.next() {
+/// Sums every line evaluated with `calculate`, `None` if one of them fails.
+fn sum(lines: &[String], calculate: fn(&str) -> Result<u64, EvalError>) -> Option<u64> {
+    lines
+        .iter()
+        .try_fold(0u64, |sum, line| sum.checked_add(calculate(line).ok()?))
+}
+
+/// Pratt parser over `lexer`, consuming operators that bind at least as tightly
+/// as `precedence`. Stops before the first token it can't continue with, such
+/// as a closing `)`, and leaves it in `lexer`.
+pub fn evaluate(
+    lexer: &mut Lexer,
+    precedence: u8,
+    binding_power: BindingPower,
+) -> Result<u64, EvalError> {
+    let mut lhs = match lexer.next_token() {
         Token::Int(ch) => ch.to_digit(10).unwrap() as u64,
         Token::Op('(') => {
-            let lhs = evaluate(lexer, 0);
-            assert_eq!(lexer.next(), Token::Op(')'));
-            lhs
+            let lhs = evaluate(lexer, 0, binding_power)?;
+            match lexer.next_token() {
+                Token::Op(')') => lhs,
+                t => return Err(EvalError::Unexpected(t)),
+            }
         }
-        t => panic!("bad token: {:?}", t),
+        t => return Err(EvalError::Unexpected(t)),
     };
 
     loop {
         let op = match lexer.peek() {
             Token::Eof => break,
             Token::Op(op) => op,
-            t => panic!("bad token: {:?}", t),
+            t => return Err(EvalError::Unexpected(t)),
         };
 
-        if let Some((lbp, rbp)) = infix_binding_power(op) {
+        if let Some((lbp, rbp)) = binding_power(op) {
             if lbp < precedence {
                 break;
             }
-            lexer.next();
-            let rhs = evaluate(lexer, rbp);
-            match op {
-                '+' => lhs += rhs,
-                '*' => lhs *= rhs,
-                _ => panic!("unknown operator: {}", op),
+            lexer.next_token();
+            let rhs = evaluate(lexer, rbp, binding_power)?;
+            lhs = match op {
+                '+' => lhs.checked_add(rhs),
+                '*' => lhs.checked_mul(rhs),
+                _ => return Err(EvalError::UnknownOperator(op)),
             }
+            .ok_or(EvalError::Overflow)?;
             continue;
         }
         break;
     }
-    lhs
+    Ok(lhs)
 }
 
-fn infix_binding_power(op: char) -> Option<(u8, u8)> {
+/// Part one's precedence: `+` and `*` bind equally tightly.
+pub fn infix_binding_power(op: char) -> Option<(u8, u8)> {
     match op {
         '+' | '*' => Some((1, 2)),
-        // part two
-        // '*' => Some((1, 2)),
-        // '+' => Some((3, 4)),
+        _ => None,
+    }
+}
+
+/// Part two's precedence: `+` binds tighter than `*`.
+pub fn advanced_binding_power(op: char) -> Option<(u8, u8)> {
+    match op {
+        '*' => Some((1, 2)),
+        '+' => Some((3, 4)),
         _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::calculate;
+    use super::{calculate, calculate_advanced, Day18, EvalError, Token};
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn parse_reports_syntax_errors() {
+        assert!(Day18.parse("1 + (2 * 3)\n4").is_ok());
+        let err = Day18.parse("1 + 2\n1 + x").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x"));
+        let err = Day18.parse("1 + (2 * 3").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "("));
+        let err = Day18.parse("1 + 2)").unwrap_err();
+        assert_eq!(err.column, 6);
+        let err = Day18.parse("12").unwrap_err();
+        assert_eq!(err.column, 2);
+        let err = Day18.parse("1 +").unwrap_err();
+        assert_eq!(err.column, 4);
+    }
 
     #[test]
     fn calculate_single_expression() {
-        assert_eq!(calculate("2"), 2);
+        assert_eq!(calculate("2"), Ok(2));
     }
 
     #[test]
     fn calculate_arith_expression() {
-        assert_eq!(calculate("2 + 5"), 7);
+        assert_eq!(calculate("2 + 5"), Ok(7));
     }
 
     #[test]
     fn calculate_deep_arith_expression() {
-        assert_eq!(calculate("3 + 5 * 2 + 1"), 17);
+        assert_eq!(calculate("3 + 5 * 2 + 1"), Ok(17));
     }
 
     #[test]
     fn calculate_first_grouped_xpression() {
-        assert_eq!(calculate("(3 + 5) * 2"), 16);
+        assert_eq!(calculate("(3 + 5) * 2"), Ok(16));
     }
 
     #[test]
     fn calculate_first_grouped_expression() {
         assert_eq!(
             calculate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"),
-            13632
+            Ok(13632)
+        );
+    }
+
+    #[test]
+    fn calculate_advanced_adds_before_multiplying() {
+        assert_eq!(calculate_advanced("1 + 2 * 3 + 4 * 5 + 6"), Ok(231));
+        assert_eq!(calculate_advanced("2 * 3 + (4 * 5)"), Ok(46));
+        assert_eq!(
+            calculate_advanced("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"),
+            Ok(23340)
+        );
+    }
+
+    #[test]
+    fn calculate_reports_bad_expressions() {
+        assert_eq!(
+            calculate("1 + x"),
+            Err(EvalError::Unexpected(Token::Op('x')))
+        );
+        assert_eq!(calculate("(1 + 2"), Err(EvalError::Unexpected(Token::Eof)));
+        assert_eq!(
+            calculate("1 2"),
+            Err(EvalError::Unexpected(Token::Int('2')))
+        );
+        assert_eq!(
+            calculate("1 + 2)"),
+            Err(EvalError::Unexpected(Token::Op(')')))
         );
+        let huge = vec!["9"; 21].join(" * ");
+        assert_eq!(calculate(&huge), Err(EvalError::Overflow));
+        assert_eq!(Day18.part_one(&vec![huge]), Answer::None);
     }
 }
@@ -1,48 +1,66 @@
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
 
-type Rules = HashMap<String, String>;
+/// Message rules by number, e.g. `"8"` to `42 | 42 8` or `"1"` to `"a"`.
+pub type Rules = HashMap<String, String>;
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day19;
 
-fn part_one() {
-    let (rules, messages) = get_input();
-    let regex = compile_regex("0", &rules, true);
-    let count = total_valid_messages(messages, regex);
-    print!("Day 19 - A: {:?}", count);
-}
+impl Solution for Day19 {
+    type Input = (Rules, Vec<String>);
 
-fn part_two() {
-    let (rules, messages) = get_input();
-    let regex_42 = compile_regex("42", &rules, false);
-    let regex_31 = compile_regex("31", &rules, false);
-    let matches = count_matches(messages, regex_42, regex_31);
-    println!(" - B: {:?}", matches);
-}
+    const DAY: u8 = 19;
 
-fn get_input() -> (Rules, Vec<String>) {
-    let file_strs = fs::read_to_string("data/day19.txt").unwrap();
-    let input: Vec<&str> = file_strs.split("\n\n").collect();
-    (
-        parse_rules(input[0]),
-        input[1].lines().map(String::from).collect(),
-    )
+    fn parse(&self, input: &str) -> Result<(Rules, Vec<String>), ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mut sections = input.splitn(2, "\n\n");
+        let rules = ctx.field(sections.next(), input, "rules")?;
+        let messages = ctx.field(sections.next(), rules, "messages")?;
+        Ok((
+            parse_rules(&ctx, rules)?,
+            messages.lines().map(String::from).collect(),
+        ))
+    }
+
+    fn part_one(&self, input: &(Rules, Vec<String>)) -> Answer {
+        let (rules, messages) = input;
+        if !rules.contains_key("0") {
+            return Answer::None;
+        }
+        let regex = compile_regex("0", rules, true);
+        let count = total_valid_messages(messages, regex);
+        count.into()
+    }
+
+    fn part_two(&self, input: &(Rules, Vec<String>)) -> Answer {
+        let (rules, messages) = input;
+        if !rules.contains_key("42") || !rules.contains_key("31") {
+            return Answer::None;
+        }
+        let regex_42 = compile_regex("42", rules, false);
+        let regex_31 = compile_regex("31", rules, false);
+        let matches = count_matches(messages, regex_42, regex_31);
+        matches.into()
+    }
 }
 
-fn total_valid_messages(messages: Vec<String>, regex: Regex) -> i32 {
+pub fn total_valid_messages(messages: &[String], regex: Regex) -> i32 {
     let mut count = 0;
     for msg in messages {
-        if regex.is_match(&msg) {
+        if regex.is_match(msg) {
             count += 1;
         }
     }
     count
 }
 
-fn count_matches(messages: Vec<String>, regex_42: Regex, regex_31: Regex) -> i32 {
+/// Counts messages matching the looping part two rules `8: 42 | 42 8` and
+/// `11: 42 31 | 42 11 31`: some 42s followed by fewer, but at least one, 31s.
+pub fn count_matches(messages: &[String], regex_42: Regex, regex_31: Regex) -> i32 {
     let mut count = 0;
     // println!("42 {}", regex_42);
     // println!("31 {}", regex_31);
@@ -51,8 +69,8 @@ fn count_matches(messages: Vec<String>, regex_42: Regex, regex_31: Regex) -> i32
 
         let mut count_42 = 0;
         loop {
-            match regex_42.find_at(&msg, start) {
-                Some(reg_match) => {
+            match regex_42.find_at(msg, start) {
+                Some(reg_match) if reg_match.start() == start => {
                     // println!(
                     //     "match42 {} - {}:{} - slice {:?}",
                     //     msg,
@@ -63,14 +81,14 @@ fn count_matches(messages: Vec<String>, regex_42: Regex, regex_31: Regex) -> i32
                     count_42 += 1;
                     start = reg_match.end();
                 }
-                None => break,
+                _ => break,
             }
         }
 
         let mut count_31 = 0;
         loop {
-            match regex_31.find_at(&msg, start) {
-                Some(reg_match) => {
+            match regex_31.find_at(msg, start) {
+                Some(reg_match) if reg_match.start() == start => {
                     // println!(
                     //     "match31 {} - {}:{} - slice {:?}",
                     //     msg,
@@ -81,7 +99,7 @@ fn count_matches(messages: Vec<String>, regex_42: Regex, regex_31: Regex) -> i32
                     count_31 += 1;
                     start = reg_match.end();
                 }
-                None => break,
+                _ => break,
             }
         }
 
@@ -93,30 +111,53 @@ fn count_matches(messages: Vec<String>, regex_42: Regex, regex_31: Regex) -> i32
     count
 }
 
-fn parse_rules(input: &str) -> Rules {
-    input.lines().fold(HashMap::new(), |mut rules, line| {
-        let rule_strs: Vec<&str> = line.split(":").collect();
-        rules.insert(rule_strs[0].to_string(), rule_strs[1].trim().to_string());
-        rules
-    })
+fn parse_rules(ctx: &Context, input: &str) -> Result<Rules, ParseError> {
+    let mut rules = HashMap::new();
+    let mut references = Vec::new();
+
+    for line in input.lines() {
+        let mut rule_strs = line.splitn(2, ':');
+        let id = rule_strs.next().unwrap_or(line);
+        let pattern = ctx.field(rule_strs.next(), id, "`:` after the rule number")?;
+        ctx.number::<u32>(id)?;
+
+        let pattern = pattern.trim();
+        if pattern.starts_with('"') {
+            if pattern.len() < 3 || !pattern.ends_with('"') {
+                return Err(ctx.error(pattern, "expected a quoted literal"));
+            }
+        } else {
+            for subrule in pattern.split(' ').filter(|&subrule| subrule != "|") {
+                ctx.number::<u32>(subrule)?;
+                references.push(subrule);
+            }
+        }
+        rules.insert(id.to_string(), pattern.to_string());
+    }
+
+    match references.into_iter().find(|id| !rules.contains_key(*id)) {
+        Some(id) => Err(ctx.error(id, "reference to an undefined rule")),
+        None => Ok(rules),
+    }
 }
 
-fn compile_regex(rule: &str, rules: &Rules, anchors: bool) -> Regex {
-    let mut regex_string = construct_regex(rule.to_string(), &rules);
+/// Compiles `rule` into a regex, anchored to the whole message when `anchors` is set.
+pub fn compile_regex(rule: &str, rules: &Rules, anchors: bool) -> Regex {
+    let mut regex_string = construct_regex(rule.to_string(), rules);
     if anchors {
         regex_string = format!("^{}$", regex_string);
     }
     Regex::new(&regex_string).unwrap()
 }
 
-fn construct_regex(rule: String, rules: &Rules) -> String {
+pub fn construct_regex(rule: String, rules: &Rules) -> String {
     if rule == "|" {
         return "|".to_string();
     }
 
     let pattern = rules.get(&rule).unwrap();
     if pattern.starts_with("\"") {
-        return pattern.trim_matches('"').to_string();
+        regex::escape(pattern.trim_matches('"'))
     } else {
         let regex: String = pattern
             .split(" ")
@@ -128,18 +169,46 @@ fn construct_regex(rule: String, rules: &Rules) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{compile_regex, construct_regex, count_matches, parse_rules, total_valid_messages};
+    use super::{compile_regex, construct_regex, count_matches, parse_rules, Day19, Rules};
+    use crate::{
+        error::Context,
+        solution::{Answer, Solution},
+    };
+
+    fn rules(input: &str) -> Rules {
+        parse_rules(&Context::new(19, input), input).unwrap()
+    }
+
+    #[test]
+    fn parse_reports_bad_rules() {
+        let input = "0: 1 2\n1: \"a\"\n2: \"b\"\n\nab\nba";
+        let (_, messages) = Day19.parse(input).unwrap();
+        assert_eq!(
+            Day19.part_one(&Day19.parse(input).unwrap()),
+            Answer::Number(1)
+        );
+        assert_eq!(messages.len(), 2);
+
+        let err = Day19.parse("0: 1 3\n1: \"a\"\n\nab").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 6, "3"));
+        let err = Day19.parse("0: 1\n1 \"a\"\n\na").unwrap_err();
+        assert_eq!(err.message, "missing `:` after the rule number");
+        let err = Day19.parse("0: 1 x\n1: \"a\"\n\na").unwrap_err();
+        assert_eq!(err.text, "x");
+        let err = Day19.parse("0: \"a\"\n").unwrap_err();
+        assert_eq!(err.message, "missing messages");
+    }
 
     #[test]
     fn matching() {
         let regex = regex::Regex::new("(a(a|b))").unwrap();
         let reg_match = regex.find_at("ab", 0).unwrap();
-        assert_eq!(reg_match.end(), 0);
+        assert_eq!(reg_match.end(), 2);
     }
 
     #[test]
     fn part2_input_works() {
-        let rules = parse_rules(
+        let rules = rules(
             "42: 9 14 | 10 1\n\
             9: 14 27 | 1 26\n\
             10: 23 14 | 28 1\n\
@@ -171,53 +240,49 @@ mod tests {
             ",
         );
         let msgs = vec![
+            "bbabbbbaabaabba".to_string(),
             "babbbbaabbbbbabbbbbbaabaaabaaa".to_string(),
-            "aabaaabaaa".to_string(),
-            // "bbabbbbaabaabba".to_string(),
-            // "babbbbaabbbbbabbbbbbaabaaabaaa".to_string(),
-            //
-            // "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa".to_string(),
-            //
-            // "aaabbbbbbaaaabaababaabababbabaaabbababababaaa".to_string(),
-            // "bbbbbbbaaaabbbbaaabbabaaa".to_string(),
-            // "bbbababbbbaaaaaaaabbababaaababaabab".to_string(),
-            // "ababaaaaaabaaab".to_string(),
-            // "ababaaaaabbbaba".to_string(),
-            // "baabbaaaabbaaaababbaababb".to_string(),
-            // "abbbbabbbbaaaababbbbbbaaaababb".to_string(),
-            // "aaaaabbaabaaaaababaa".to_string(),
-            // "aaaabbaaaabbaaa".to_string(),
-            // "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa".to_string(),
-            // "babaaabbbaaabaababbaabababaaab".to_string(),
-            // "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba".to_string(),
+            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa".to_string(),
+            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa".to_string(),
+            "bbbbbbbaaaabbbbaaabbabaaa".to_string(),
+            "bbbababbbbaaaaaaaabbababaaababaabab".to_string(),
+            "ababaaaaaabaaab".to_string(),
+            "ababaaaaabbbaba".to_string(),
+            "baabbaaaabbaaaababbaababb".to_string(),
+            "abbbbabbbbaaaababbbbbbaaaababb".to_string(),
+            "aaaaabbaabaaaaababaa".to_string(),
+            "aaaabbaaaabbaaa".to_string(),
+            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa".to_string(),
+            "babaaabbbaaabaababbaabababaaab".to_string(),
+            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba".to_string(),
         ];
         let regex_42 = compile_regex("42", &rules, false);
         let regex_31 = compile_regex("31", &rules, false);
-        let count = count_matches(msgs, regex_42, regex_31);
+        let count = count_matches(&msgs, regex_42, regex_31);
         assert_eq!(count, 12);
     }
 
     #[test]
     fn regex_matches_exactly() {
-        let rules = parse_rules("0: 1 2 | 2 1\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
+        let rules = rules("0: 1 2 | 2 1\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
         let regex = compile_regex("0", &rules, true);
-        assert_eq!(regex.is_match("aab"), true);
-        assert_eq!(regex.is_match("aba"), true);
-        assert_eq!(regex.is_match("baa"), true);
-        assert_eq!(regex.is_match("baaa"), false);
-        assert_eq!(regex.is_match("aaab"), false);
+        assert!(regex.is_match("aab"));
+        assert!(regex.is_match("aba"));
+        assert!(regex.is_match("baa"));
+        assert!(!regex.is_match("baaa"));
+        assert!(!regex.is_match("aaab"));
     }
 
     #[test]
     fn construct_regex_works_with_ors() {
-        let rules = parse_rules("0: 1 2 | 2 1\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
+        let rules = rules("0: 1 2 | 2 1\n1: \"a\"\n2: 1 3 | 3 1\n3: \"b\"");
         let regex = construct_regex("0".to_string(), &rules);
         assert_eq!(regex, "(a(ab|ba)|(ab|ba)a)");
     }
 
     #[test]
     fn construct_regex_works() {
-        let rules = parse_rules("0: 1 2\n1: \"a\"\n2: \"b\"");
+        let rules = rules("0: 1 2\n1: \"a\"\n2: \"b\"");
         let regex = construct_regex("0".to_string(), &rules);
         assert_eq!(regex, "(ab)");
     }
@@ -1,92 +1,834 @@
-use std::{fs, ops::Range};
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
 
-#[derive(Debug)]
-struct Policy {
-    range: Range<i32>,
-    character: char,
+/// The `lo-hi c` part of a record. What `low` and `high` mean depends on the
+/// policy a record is checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub low: usize,
+    pub high: usize,
+    pub character: char,
 }
 
-impl Policy {
-    fn new(data: String) -> Self {
-        let data: Vec<&str> = data.split(' ').collect();
-        let range: Vec<&str> = data[0].split('-').collect();
-        Self {
-            range: (range[0].parse().unwrap()..range[1].parse::<i32>().unwrap() + 1),
-            character: data[1].chars().nth(0).or_else(|| Some(' ')).unwrap(),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub requirement: Requirement,
+    pub password: String,
+}
+
+/// What is wrong with a record that doesn't match `lo-hi c: password`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordErrorKind {
+    BadRange,
+    BadCharacter,
+    MissingColon,
+    MissingPassword,
+    ReversedRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    pub kind: RecordErrorKind,
+    pub error: ParseError,
+}
+
+impl From<RecordError> for ParseError {
+    fn from(err: RecordError) -> Self {
+        err.error
+    }
+}
+
+impl Record {
+    /// Parses one `lo-hi c: password` line. `line` must be a slice of the
+    /// context's input so errors point at the right line and column.
+    pub fn parse(ctx: &Context, line: &str) -> Result<Self, RecordError> {
+        let fail = |kind, text: &str, message: &str| RecordError {
+            kind,
+            error: ctx.error(text, message),
+        };
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let (low, rest) = split_digits(line);
+        if low.is_empty() {
+            return Err(fail(
+                RecordErrorKind::BadRange,
+                first_char(line),
+                "expected a number",
+            ));
+        }
+        let rest = rest
+            .strip_prefix('-')
+            .ok_or_else(|| fail(RecordErrorKind::BadRange, first_char(rest), "expected `-`"))?;
+        let (high, rest) = split_digits(rest);
+        if high.is_empty() {
+            return Err(fail(
+                RecordErrorKind::BadRange,
+                first_char(rest),
+                "expected a number",
+            ));
+        }
+        let rest = rest.strip_prefix(' ').ok_or_else(|| {
+            fail(
+                RecordErrorKind::BadCharacter,
+                first_char(rest),
+                "expected a space before the policy character",
+            )
+        })?;
+
+        let character_len = match rest.find(&[':', ' '][..]) {
+            Some(0) if rest.starts_with(':') => 1,
+            Some(end) => end,
+            None => rest.len(),
+        };
+        let (character, rest) = rest.split_at(character_len);
+        let mut chars = character.chars();
+        let character = match (chars.next(), chars.next()) {
+            (Some(ch), None) => ch,
+            _ => {
+                return Err(fail(
+                    RecordErrorKind::BadCharacter,
+                    character,
+                    "expected a single policy character",
+                ))
+            }
+        };
+        let rest = rest.strip_prefix(':').ok_or_else(|| RecordError {
+            kind: RecordErrorKind::MissingColon,
+            error: ctx.missing(
+                &line[..line.len() - rest.len()],
+                "missing `:` after the policy",
+            ),
+        })?;
+        let password = rest.strip_prefix(' ').unwrap_or(rest);
+        if password.is_empty() {
+            return Err(RecordError {
+                kind: RecordErrorKind::MissingPassword,
+                error: ctx.missing(rest, "missing password"),
+            });
+        }
+
+        let number = |text| {
+            ctx.number::<usize>(text).map_err(|error| RecordError {
+                kind: RecordErrorKind::BadRange,
+                error,
+            })
+        };
+        let requirement = Requirement {
+            low: number(low)?,
+            high: number(high)?,
+            character,
+        };
+        if requirement.low > requirement.high {
+            return Err(fail(
+                RecordErrorKind::ReversedRange,
+                &line[..low.len() + 1 + high.len()],
+                "range start is after its end",
+            ));
         }
+
+        Ok(Self {
+            requirement,
+            password: password.to_string(),
+        })
     }
 }
 
-struct Record {
-    policy: Policy,
-    password: String,
+/// Parses every line of `input` on its own, so bad records can be reported and
+/// the rest still used.
+pub fn parse_records(input: &str) -> Vec<Result<Record, RecordError>> {
+    let ctx = Context::new(Day2::DAY, input);
+    input
+        .lines()
+        .map(|line| Record::parse(&ctx, line))
+        .collect()
 }
 
-impl Record {
-    fn new(data: &str) -> Self {
-        let data: Vec<String> = data.split(':').map(String::from).collect();
-        Self {
-            policy: Policy::new(data[0].to_owned()),
-            password: data[1].trim().to_owned(),
+fn split_digits(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn first_char(text: &str) -> &str {
+    text.chars()
+        .next()
+        .map_or(&text[..0], |ch| &text[..ch.len_utf8()])
+}
+
+/// A rule a password can be checked against.
+pub trait PasswordPolicy {
+    fn name(&self) -> &str;
+
+    /// Returns why `record` violates the policy, or `None` if it complies.
+    fn violation(&self, record: &Record) -> Option<String>;
+}
+
+/// The sled rental policy: the character occurs between `low` and `high` times.
+pub struct OccurrenceRange;
+
+impl PasswordPolicy for OccurrenceRange {
+    fn name(&self) -> &str {
+        "occurrence range"
+    }
+
+    fn violation(&self, record: &Record) -> Option<String> {
+        let Requirement {
+            low,
+            high,
+            character,
+        } = record.requirement;
+        let count = record
+            .password
+            .chars()
+            .filter(|&ch| ch == character)
+            .count();
+        if (low..=high).contains(&count) {
+            None
+        } else {
+            Some(format!(
+                "`{}` occurs {} times, expected {} to {}",
+                character, count, low, high
+            ))
+        }
+    }
+}
+
+/// The Toboggan policy: exactly one of the 1-based positions `low` and `high`
+/// holds the character.
+pub struct ExactlyOnePosition;
+
+impl PasswordPolicy for ExactlyOnePosition {
+    fn name(&self) -> &str {
+        "exactly one position"
+    }
+
+    fn violation(&self, record: &Record) -> Option<String> {
+        let Requirement {
+            low,
+            high,
+            character,
+        } = record.requirement;
+        if low == 0 {
+            return Some("positions start at 1, not 0".to_string());
+        }
+        let len = record.password.chars().count();
+        if high > len {
+            return Some(format!(
+                "position {} is past the end of the {}-character password",
+                high, len
+            ));
+        }
+        let at = |position: usize| {
+            position
+                .checked_sub(1)
+                .and_then(|i| record.password.chars().nth(i))
+                == Some(character)
+        };
+        match (at(low), at(high)) {
+            (true, false) | (false, true) => None,
+            (true, true) => Some(format!(
+                "`{}` is at both positions {} and {}",
+                character, low, high
+            )),
+            (false, false) => Some(format!(
+                "`{}` is at neither position {} nor {}",
+                character, low, high
+            )),
         }
     }
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+/// The password is between `min` and `max` characters long.
+pub struct LengthRange {
+    pub min: usize,
+    pub max: usize,
 }
 
-fn part_one() {
-    let mut count = 0;
-    let records = get_password_records();
-    for record in &records {
-        let occurrence = occurrence_of_character(&record);
-        if record.policy.range.contains(&occurrence) {
-            count += 1;
+impl PasswordPolicy for LengthRange {
+    fn name(&self) -> &str {
+        "length"
+    }
+
+    fn violation(&self, record: &Record) -> Option<String> {
+        let len = record.password.chars().count();
+        if (self.min..=self.max).contains(&len) {
+            None
+        } else {
+            Some(format!(
+                "{} characters long, expected {} to {}",
+                len, self.min, self.max
+            ))
         }
     }
+}
 
-    print!("Day 2 - A: {:?}", count);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    /// Anything that isn't alphanumeric or whitespace.
+    Symbol,
 }
 
-fn part_two() {
-    let mut count = 0;
-    let records = get_password_records();
-    for record in &records {
-        let pos1 = record
-            .password
-            .chars()
-            .nth((record.policy.range.start - 1) as usize)
-            .unwrap();
-        let pos2 = record
-            .password
-            .chars()
-            .nth((record.policy.range.end - 2) as usize)
-            .unwrap();
-        let ch = record.policy.character;
+impl CharClass {
+    pub fn contains(self, ch: char) -> bool {
+        match self {
+            CharClass::Lowercase => ch.is_lowercase(),
+            CharClass::Uppercase => ch.is_uppercase(),
+            CharClass::Digit => ch.is_numeric(),
+            CharClass::Symbol => !ch.is_alphanumeric() && !ch.is_whitespace(),
+        }
+    }
+}
+
+impl FromStr for CharClass {
+    type Err = String;
 
-        if pos1 == ch && pos2 != ch || pos1 != ch && pos2 == ch {
-            count += 1;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "lower" => Ok(CharClass::Lowercase),
+            "upper" => Ok(CharClass::Uppercase),
+            "digit" => Ok(CharClass::Digit),
+            "symbol" => Ok(CharClass::Symbol),
+            _ => Err(format!(
+                "unknown character class `{}`, expected `lower`, `upper`, `digit` or `symbol`",
+                value
+            )),
         }
     }
+}
 
-    println!(", B: {:?}", count);
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharClass::Lowercase => write!(f, "lowercase letter"),
+            CharClass::Uppercase => write!(f, "uppercase letter"),
+            CharClass::Digit => write!(f, "digit"),
+            CharClass::Symbol => write!(f, "symbol"),
+        }
+    }
 }
 
-fn occurrence_of_character(record: &Record) -> i32 {
-    record
-        .password
-        .chars()
-        .filter(|&c| c == record.policy.character)
-        .collect::<Vec<char>>()
-        .len() as i32
+/// The password contains at least one character of every class.
+pub struct RequiredClasses(pub Vec<CharClass>);
+
+impl PasswordPolicy for RequiredClasses {
+    fn name(&self) -> &str {
+        "character classes"
+    }
+
+    fn violation(&self, record: &Record) -> Option<String> {
+        let missing: Vec<String> = self
+            .0
+            .iter()
+            .filter(|class| !record.password.chars().any(|ch| class.contains(ch)))
+            .map(|class| class.to_string())
+            .collect();
+        if missing.is_empty() {
+            None
+        } else {
+            Some(format!("no {}", missing.join(", no ")))
+        }
+    }
 }
 
-fn get_password_records() -> Vec<Record> {
-    fs::read_to_string("data/day2.txt")
-        .unwrap()
-        .lines()
-        .map(Record::new)
-        .collect()
+/// The password contains none of the substrings.
+pub struct ForbiddenSubstrings(pub Vec<String>);
+
+impl PasswordPolicy for ForbiddenSubstrings {
+    fn name(&self) -> &str {
+        "forbidden substrings"
+    }
+
+    fn violation(&self, record: &Record) -> Option<String> {
+        let found: Vec<String> = self
+            .0
+            .iter()
+            .filter(|substring| record.password.contains(substring.as_str()))
+            .map(|substring| format!("`{}`", substring))
+            .collect();
+        if found.is_empty() {
+            None
+        } else {
+            Some(format!("contains {}", found.join(", ")))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub policy: String,
+    pub reason: String,
+}
+
+/// Checks records against a set of policies at once.
+pub struct Checker {
+    policies: Vec<Box<dyn PasswordPolicy>>,
+}
+
+impl Checker {
+    pub fn new(policies: Vec<Box<dyn PasswordPolicy>>) -> Self {
+        Self { policies }
+    }
+
+    /// Every policy `record` violates, in the order the policies were given.
+    pub fn check(&self, record: &Record) -> Vec<Violation> {
+        self.policies
+            .iter()
+            .filter_map(|policy| {
+                policy.violation(record).map(|reason| Violation {
+                    policy: policy.name().to_string(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    /// Index and violations of every record that breaks at least one policy.
+    pub fn failures(&self, records: &[Record]) -> Vec<(usize, Vec<Violation>)> {
+        records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| (i, self.check(record)))
+            .filter(|(_, violations)| !violations.is_empty())
+            .collect()
+    }
+}
+
+/// Which records `audit` copies to its output as it reads them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    Nothing,
+    Valid,
+    Invalid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyTotals {
+    pub policy: String,
+    pub valid: usize,
+    pub invalid: usize,
+}
+
+/// Summary of a password database checked by `audit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub records: usize,
+    pub policies: Vec<PolicyTotals>,
+    /// Lines of well-formed records that violate at least one policy.
+    pub failing_lines: Vec<usize>,
+    /// Lines that are not `lo-hi c: password`, skipped by every policy.
+    pub malformed: Vec<ParseError>,
+    /// Policy characters by how many records require them, most required first.
+    pub required_characters: Vec<(char, usize)>,
+}
+
+/// Checks a password database line by line without holding it in memory, copying
+/// valid or invalid records to `out` as selected by `emit`. Malformed records are
+/// reported and skipped, as are lines that are not valid UTF-8; a record is valid
+/// when it passes every policy.
+pub fn audit(
+    reader: impl BufRead,
+    checker: &Checker,
+    emit: Emit,
+    out: &mut impl Write,
+) -> io::Result<Report> {
+    let mut report = Report {
+        records: 0,
+        policies: checker
+            .policies
+            .iter()
+            .map(|policy| PolicyTotals {
+                policy: policy.name().to_string(),
+                valid: 0,
+                invalid: 0,
+            })
+            .collect(),
+        failing_lines: Vec::new(),
+        malformed: Vec::new(),
+        required_characters: Vec::new(),
+    };
+    let mut required: HashMap<char, usize> = HashMap::new();
+
+    let mut reader = reader;
+    let mut bytes = Vec::new();
+    for i in 0.. {
+        bytes.clear();
+        if reader.read_until(b'\n', &mut bytes)? == 0 {
+            break;
+        }
+        let bytes = bytes.strip_suffix(b"\n").unwrap_or(&bytes);
+        report.records += 1;
+        let line = match std::str::from_utf8(bytes) {
+            Ok(line) => line,
+            Err(invalid) => {
+                let line = String::from_utf8_lossy(bytes);
+                let start = invalid.valid_up_to();
+                let end = start + char::REPLACEMENT_CHARACTER.len_utf8();
+                let mut error =
+                    Context::new(Day2::DAY, &line).error(&line[start..end], "expected valid UTF-8");
+                error.line = i + 1;
+                report.malformed.push(error);
+                continue;
+            }
+        };
+        let record = match Record::parse(&Context::new(Day2::DAY, line), line) {
+            Ok(record) => record,
+            Err(RecordError { mut error, .. }) => {
+                error.line = i + 1;
+                report.malformed.push(error);
+                continue;
+            }
+        };
+        *required.entry(record.requirement.character).or_default() += 1;
+
+        let mut valid = true;
+        for (policy, totals) in checker.policies.iter().zip(&mut report.policies) {
+            match policy.violation(&record) {
+                None => totals.valid += 1,
+                Some(_) => {
+                    totals.invalid += 1;
+                    valid = false;
+                }
+            }
+        }
+        if !valid {
+            report.failing_lines.push(i + 1);
+        }
+        if (emit == Emit::Valid && valid) || (emit == Emit::Invalid && !valid) {
+            writeln!(out, "{}", line)?;
+        }
+    }
+
+    report.required_characters = required.into_iter().collect();
+    report
+        .required_characters
+        .sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    Ok(report)
+}
+
+const SHOWN_LINES: usize = 20;
+const SHOWN_CHARACTERS: usize = 5;
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} records, {} malformed",
+            self.records,
+            self.malformed.len()
+        )?;
+        writeln!(f, "{:<24}  {:>10}  {:>10}", "policy", "valid", "invalid")?;
+        for totals in &self.policies {
+            writeln!(
+                f,
+                "{:<24}  {:>10}  {:>10}",
+                totals.policy, totals.valid, totals.invalid
+            )?;
+        }
+
+        let lines: Vec<String> = self
+            .failing_lines
+            .iter()
+            .take(SHOWN_LINES)
+            .map(usize::to_string)
+            .collect();
+        write!(
+            f,
+            "failing lines ({}): {}",
+            self.failing_lines.len(),
+            lines.join(", ")
+        )?;
+        if self.failing_lines.len() > SHOWN_LINES {
+            write!(f, ", ...")?;
+        }
+        writeln!(f)?;
+
+        let characters: Vec<String> = self
+            .required_characters
+            .iter()
+            .take(SHOWN_CHARACTERS)
+            .map(|(ch, count)| format!("`{}` ({})", ch, count))
+            .collect();
+        writeln!(f, "most required characters: {}", characters.join(", "))?;
+
+        for error in &self.malformed {
+            writeln!(f, "skipped {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Record>;
+
+    const DAY: u8 = 2;
+
+    fn parse(&self, input: &str) -> Result<Vec<Record>, ParseError> {
+        parse_records(input)
+            .into_iter()
+            .map(|record| record.map_err(ParseError::from))
+            .collect()
+    }
+
+    fn part_one(&self, records: &Vec<Record>) -> Answer {
+        count_valid(records, &OccurrenceRange).into()
+    }
+
+    fn part_two(&self, records: &Vec<Record>) -> Answer {
+        count_valid(records, &ExactlyOnePosition).into()
+    }
+}
+
+fn count_valid(records: &[Record], policy: &dyn PasswordPolicy) -> usize {
+    records
+        .iter()
+        .filter(|record| policy.violation(record).is_none())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        audit, parse_records, CharClass, Checker, Day2, Emit, ExactlyOnePosition,
+        ForbiddenSubstrings, LengthRange, OccurrenceRange, PasswordPolicy, RecordErrorKind,
+        RequiredClasses, Violation,
+    };
+    use crate::solution::{Answer, Solution};
+
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc";
+
+    #[test]
+    fn parse_reports_malformed_records() {
+        let err = Day2.parse("1-3 a: abcde\n1-x b: cdefg").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+
+        let err = Day2.parse("1-3 a abcde").err().unwrap();
+        assert_eq!(err.message, "missing `:` after the policy");
+
+        let err = Day2.parse("2-9 cc: ccccccccc").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (5, "cc"));
+    }
+
+    #[test]
+    fn existing_policies_explain_violations() {
+        let records = Day2.parse(EXAMPLE).unwrap();
+        assert_eq!(OccurrenceRange.violation(&records[0]), None);
+        assert_eq!(
+            OccurrenceRange.violation(&records[1]),
+            Some("`b` occurs 0 times, expected 1 to 3".to_string())
+        );
+        assert_eq!(ExactlyOnePosition.violation(&records[0]), None);
+        assert_eq!(
+            ExactlyOnePosition.violation(&records[2]),
+            Some("`c` is at both positions 2 and 9".to_string())
+        );
+    }
+
+    #[test]
+    fn new_policies_check_length_classes_and_substrings() {
+        let records = Day2.parse("1-1 a: abc\n1-1 a: Passw0rd!").unwrap();
+        let length = LengthRange { min: 8, max: 64 };
+        assert!(length.violation(&records[0]).is_some());
+        assert!(length.violation(&records[1]).is_none());
+
+        let classes = RequiredClasses(vec![CharClass::Uppercase, CharClass::Digit]);
+        assert_eq!(
+            classes.violation(&records[0]),
+            Some("no uppercase letter, no digit".to_string())
+        );
+        assert!(classes.violation(&records[1]).is_none());
+
+        let forbidden = ForbiddenSubstrings(vec!["ssw".to_string(), "abc".to_string()]);
+        assert_eq!(
+            forbidden.violation(&records[1]),
+            Some("contains `ssw`".to_string())
+        );
+    }
+
+    #[test]
+    fn checker_reports_each_violated_policy() {
+        let records = Day2.parse(EXAMPLE).unwrap();
+        let checker = Checker::new(vec![
+            Box::new(OccurrenceRange),
+            Box::new(ExactlyOnePosition),
+            Box::new(LengthRange { min: 6, max: 20 }),
+        ]);
+        let failures = checker.failures(&records);
+        assert_eq!(failures.len(), 3);
+        assert_eq!(failures[0].0, 0);
+        assert_eq!(
+            failures[0].1,
+            vec![Violation {
+                policy: "length".to_string(),
+                reason: "5 characters long, expected 6 to 20".to_string(),
+            }]
+        );
+        let policies: Vec<&str> = failures[1].1.iter().map(|v| v.policy.as_str()).collect();
+        assert_eq!(
+            policies,
+            ["occurrence range", "exactly one position", "length"]
+        );
+    }
+
+    fn error_kind(line: &str) -> RecordErrorKind {
+        parse_records(line).remove(0).unwrap_err().kind
+    }
+
+    #[test]
+    fn parse_checks_the_syntax_and_range_order() {
+        assert_eq!(error_kind("3-1 a: abcde"), RecordErrorKind::ReversedRange);
+        assert_eq!(error_kind("1-3 a:"), RecordErrorKind::MissingPassword);
+        assert_eq!(error_kind("1-3 a abcde"), RecordErrorKind::MissingColon);
+        assert_eq!(error_kind("1 a: abcde"), RecordErrorKind::BadRange);
+        assert_eq!(error_kind("1-3 ab: abcde"), RecordErrorKind::BadCharacter);
+
+        let err = Day2.parse("1-3 a: abcde\n7-2 b: bbb").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "7-2"));
+    }
+
+    #[test]
+    fn positions_outside_the_password_only_break_the_position_policy() {
+        let records = Day2.parse("1-9 a: aaa\n0-2 b: abc\n2-3 c: ccc").unwrap();
+        let occurrences: Vec<_> = records
+            .iter()
+            .map(|record| OccurrenceRange.violation(record))
+            .collect();
+        assert_eq!(occurrences, vec![None, None, None]);
+
+        let positions: Vec<_> = records
+            .iter()
+            .map(|record| ExactlyOnePosition.violation(record))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                Some("position 9 is past the end of the 3-character password".to_string()),
+                Some("positions start at 1, not 0".to_string()),
+                Some("`c` is at both positions 2 and 3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_counts_positions_in_characters() {
+        let records = Day2.parse("1-3 é: éaé\n1-2 ü: üx\r\n2-3 :: a::").unwrap();
+        assert_eq!(records[0].password, "éaé");
+        assert_eq!(records[1].password, "üx");
+        assert_eq!(records[2].requirement.character, ':');
+        assert_eq!(Day2.part_one(&records), Answer::Number(3));
+        assert_eq!(Day2.part_two(&records), Answer::Number(1));
+
+        let records = Day2.parse("1-4 é: éaé").unwrap();
+        assert_eq!(
+            ExactlyOnePosition.violation(&records[0]),
+            Some("position 4 is past the end of the 3-character password".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_records_keeps_going_after_bad_lines() {
+        let records = parse_records("1-3 a: abcde\n1-3 a abcde\n2-9 c: ccccccccc");
+        assert!(records[0].is_ok() && records[2].is_ok());
+        let err = records[1].as_ref().unwrap_err();
+        assert_eq!(err.kind, RecordErrorKind::MissingColon);
+        assert_eq!((err.error.line, err.error.column), (2, 6));
+    }
+
+    fn existing_policies() -> Checker {
+        Checker::new(vec![
+            Box::new(OccurrenceRange),
+            Box::new(ExactlyOnePosition),
+        ])
+    }
+
+    #[test]
+    fn audit_totals_each_policy_and_lists_failing_lines() {
+        let input = format!("{}\n1-3 a abcde\n1-2 c: cab", EXAMPLE);
+        let mut out = Vec::new();
+        let report = audit(
+            input.as_bytes(),
+            &existing_policies(),
+            Emit::Nothing,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(report.records, 5);
+        assert_eq!(
+            (report.policies[0].valid, report.policies[0].invalid),
+            (3, 1)
+        );
+        assert_eq!(
+            (report.policies[1].valid, report.policies[1].invalid),
+            (2, 2)
+        );
+        assert_eq!(report.failing_lines, vec![2, 3]);
+        assert_eq!(report.malformed.len(), 1);
+        assert_eq!(report.malformed[0].line, 4);
+        assert_eq!(
+            report.required_characters,
+            vec![('c', 2), ('a', 1), ('b', 1)]
+        );
+        assert!(out.is_empty());
+
+        let text = report.to_string();
+        assert!(text.contains("failing lines (2): 2, 3\n"));
+        assert!(text.contains("skipped day 2, line 4, column 6"));
+    }
+
+    #[test]
+    fn audit_skips_lines_that_are_not_utf8() {
+        let input = b"1-3 a: abcde\n1-3 b: \xff\xfe\n2-9 c: ccccccccc\n";
+        let mut out = Vec::new();
+        let report = audit(input.as_ref(), &existing_policies(), Emit::Valid, &mut out).unwrap();
+
+        assert_eq!(report.records, 3);
+        assert_eq!(report.malformed.len(), 1);
+        assert_eq!(
+            (report.malformed[0].line, report.malformed[0].column),
+            (2, 8)
+        );
+        assert_eq!(report.malformed[0].message, "expected valid UTF-8");
+        assert_eq!(report.failing_lines, vec![3]);
+        assert_eq!(String::from_utf8(out).unwrap(), "1-3 a: abcde\n");
+    }
+
+    #[test]
+    fn audit_copies_only_the_selected_records() {
+        let mut valid = Vec::new();
+        audit(
+            EXAMPLE.as_bytes(),
+            &existing_policies(),
+            Emit::Valid,
+            &mut valid,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(valid).unwrap(), "1-3 a: abcde\n");
+
+        let mut invalid = Vec::new();
+        audit(
+            EXAMPLE.as_bytes(),
+            &existing_policies(),
+            Emit::Invalid,
+            &mut invalid,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(invalid).unwrap(),
+            "1-3 b: cdefg\n2-9 c: ccccccccc\n"
+        );
+    }
 }
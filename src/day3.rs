@@ -1,71 +1,408 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
+use std::{
+    io::{self, BufRead},
+    ops::RangeInclusive,
+};
 
-type Map = Vec<String>;
+const WORD: usize = u64::BITS as usize;
 
-struct Slope {
-    right: usize,
-    down: usize,
+/// The toboggan map, one bit per square. The pattern repeats to the right
+/// forever, so columns wrap around the width instead of the map being copied
+/// out to some fixed size.
+#[derive(Debug)]
+pub struct Grid {
+    trees: Vec<u64>,
+    width: usize,
+    height: usize,
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+impl Grid {
+    /// Builds a grid from rows of `.` and `#`, which must all be the same width.
+    pub fn new<R: AsRef<str>>(rows: impl IntoIterator<Item = R>) -> Grid {
+        let mut rows = rows.into_iter().peekable();
+        let width = rows.peek().map_or(0, |row| row.as_ref().len());
+        let mut grid = Grid::with_width(width);
+        for row in rows {
+            grid.push_row(row.as_ref().as_bytes());
+        }
+        grid
+    }
+
+    /// An empty grid whose rows will be `width` squares wide.
+    pub fn with_width(width: usize) -> Grid {
+        Grid {
+            trees: Vec::new(),
+            width,
+            height: 0,
+        }
+    }
+
+    /// Appends a row of `.` and `#`, which must match the grid's width.
+    pub fn push_row(&mut self, row: &[u8]) {
+        assert_eq!(
+            row.len(),
+            self.width,
+            "every row of the map must be the same width"
+        );
+        let start = self.trees.len();
+        self.trees.resize(start + self.stride(), 0);
+        for (col, _) in row
+            .iter()
+            .enumerate()
+            .filter(|&(_, &square)| square == b'#')
+        {
+            self.trees[start + col / WORD] |= 1 << (col % WORD);
+        }
+        self.height += 1;
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Whether there's a tree at `row`, with `col` wrapping around the width.
+    pub fn is_tree(&self, row: usize, col: usize) -> bool {
+        assert!(row < self.height, "row {} is below the map", row);
+        let col = col % self.width;
+        self.trees[row * self.stride() + col / WORD] >> (col % WORD) & 1 == 1
+    }
+
+    /// The number of words each row takes up.
+    fn stride(&self) -> usize {
+        self.width.div_ceil(WORD)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slope {
+    pub right: usize,
+    pub down: usize,
 }
 
-fn part_one() {
-    let map = expand_map(get_map());
-    let slope = Slope { right: 3, down: 1 };
-    print!("Day 3 - A: {:?}", tree_count_for_slope(&map, &slope));
+impl Slope {
+    /// The squares landed on after leaving the top left corner of a map `width`
+    /// squares wide, as `(row, col)` with `col` not yet wrapped. `right` is
+    /// reduced modulo `width` first, which lands on the same squares while
+    /// keeping `col` below `height * width`. `down` must be at least 1.
+    pub fn path(self, height: usize, width: usize) -> impl Iterator<Item = (usize, usize)> {
+        assert!(self.down > 0, "a slope has to go down to reach the bottom");
+        let right = self.right.checked_rem(width).unwrap_or(0);
+        (1..)
+            .map_while(move |step: usize| Some((step.checked_mul(self.down)?, step * right)))
+            .take_while(move |&(row, _)| row < height)
+    }
+}
+
+/// The slopes hitting the fewest and the most trees out of those searched.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Extremes {
+    pub fewest: (Slope, i32),
+    pub most: (Slope, i32),
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Grid;
+
+    const DAY: u8 = 3;
+
+    fn parse(&self, input: &str) -> Result<Grid, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        let mut lines = input.lines();
+        let first = lines.next().unwrap_or_default();
+        if first.is_empty() {
+            return Err(ctx.missing(input, "expected a map"));
+        }
+
+        let mut map = Grid::with_width(first.len());
+        for line in std::iter::once(first).chain(lines) {
+            check_row(&ctx, line, map.width())?;
+            map.push_row(line.as_bytes());
+        }
+        Ok(map)
+    }
+
+    fn part_one(&self, map: &Grid) -> Answer {
+        let slope = Slope { right: 3, down: 1 };
+        tree_count_for_slope(map, &slope).into()
+    }
+
+    fn part_two(&self, map: &Grid) -> Answer {
+        let slopes = [
+            Slope { right: 1, down: 1 },
+            Slope { right: 3, down: 1 },
+            Slope { right: 5, down: 1 },
+            Slope { right: 7, down: 1 },
+            Slope { right: 1, down: 2 },
+        ];
+
+        let trees: i64 = slopes
+            .iter()
+            .map(|slope| tree_count_for_slope(map, slope) as i64)
+            .product();
+        trees.into()
+    }
 }
 
-fn part_two() {
-    let map = expand_map(get_map());
-    let slopes = vec![
-        Slope { right: 1, down: 1 },
-        Slope { right: 3, down: 1 },
-        Slope { right: 5, down: 1 },
-        Slope { right: 7, down: 1 },
-        Slope { right: 1, down: 2 },
-    ];
-
-    let trees = slopes
-        .iter()
-        .map(|slope| tree_count_for_slope(&map, slope))
-        .fold(1, |product, count| product * count);
-
-    println!(", B: {:?}", trees);
+fn check_row(ctx: &Context, line: &str, width: usize) -> Result<(), ParseError> {
+    ctx.expect_chars(line, ".#")?;
+    if line.len() != width {
+        return Err(ctx.error(line, format!("expected a row {} squares wide", width)));
+    }
+    Ok(())
 }
 
-fn tree_count_for_slope(map: &Map, slope: &Slope) -> i32 {
-    let mut index = 0;
-    let mut trees = 0;
+/// Counts the trees hit by each slope while reading the map a row at a time,
+/// so maps too big to hold in memory can still be checked. Malformed rows are
+/// reported as `InvalidData` errors wrapping the located `ParseError`.
+pub fn stream_tree_counts(reader: impl BufRead, slopes: &[Slope]) -> io::Result<Vec<i32>> {
+    assert!(
+        slopes.iter().all(|slope| slope.down > 0),
+        "a slope has to go down to reach the bottom"
+    );
+    let mut counts = vec![0; slopes.len()];
+    let mut width = None;
 
-    for (n, layer) in map.iter().step_by(slope.down).enumerate() {
-        if n == 0 {
-            continue;
+    for (row, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        let ctx = Context::new(Day3::DAY, line);
+        let width = *width.get_or_insert(line.len());
+        if width == 0 {
+            let mut error = ctx.missing(line, "expected a map");
+            error.line = row + 1;
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        if let Err(mut error) = check_row(&ctx, line, width) {
+            error.line = row + 1;
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
         }
 
-        index += slope.right;
-        if layer.chars().nth(index).unwrap() == '#' {
-            trees += 1;
+        let squares = line.as_bytes();
+        for (slope, count) in slopes.iter().zip(&mut counts) {
+            if row > 0
+                && row % slope.down == 0
+                && squares[row / slope.down * (slope.right % width) % width] == b'#'
+            {
+                *count += 1;
+            }
         }
     }
-    trees
+    Ok(counts)
+}
+
+pub fn tree_count_for_slope(map: &Grid, slope: &Slope) -> i32 {
+    slope
+        .path(map.height(), map.width())
+        .filter(|&(row, col)| map.is_tree(row, col))
+        .count() as i32
 }
 
-fn expand_map(map: Map) -> Map {
-    let expansion_multiplier = 81; // (rows - 1) * (cols - 1) / (cols / right) round down / cols
-    let mut expanded_map = Vec::new();
-    for layer in map {
-        expanded_map.push(layer.repeat(expansion_multiplier));
+/// Tries every slope with `right` and `down` in the given ranges. Ties go to
+/// the slope found first, ordered by `right` and then `down`.
+pub fn search_slopes(
+    map: &Grid,
+    rights: RangeInclusive<usize>,
+    downs: RangeInclusive<usize>,
+) -> Option<Extremes> {
+    let mut extremes: Option<Extremes> = None;
+    for right in rights {
+        for down in downs.clone() {
+            let slope = Slope { right, down };
+            let trees = tree_count_for_slope(map, &slope);
+            match extremes.as_mut() {
+                None => {
+                    extremes = Some(Extremes {
+                        fewest: (slope, trees),
+                        most: (slope, trees),
+                    })
+                }
+                Some(found) => {
+                    if trees < found.fewest.1 {
+                        found.fewest = (slope, trees);
+                    }
+                    if trees > found.most.1 {
+                        found.most = (slope, trees);
+                    }
+                }
+            }
+        }
     }
-    expanded_map
+    extremes
 }
 
-fn get_map() -> Map {
-    fs::read_to_string("data/day3.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
+/// Draws the map repeated far enough to the right to cover the whole path,
+/// marking the squares landed on with `O`, or `X` where there's a tree. A slope
+/// going right by the width or more is drawn as its equivalent narrower slope.
+pub fn render_path(map: &Grid, slope: &Slope) -> String {
+    let path: Vec<(usize, usize)> = slope.path(map.height(), map.width()).collect();
+    let columns = path.last().map_or(0, |&(_, col)| col) / map.width() * map.width() + map.width();
+
+    let mut rendered = String::with_capacity((columns + 1) * map.height());
+    let mut landings = path.iter().peekable();
+    for row in 0..map.height() {
+        let landed = landings.next_if(|&&(r, _)| r == row).map(|&(_, col)| col);
+        for col in 0..columns {
+            rendered.push(match (landed == Some(col), map.is_tree(row, col)) {
+                (true, true) => 'X',
+                (true, false) => 'O',
+                (false, true) => '#',
+                (false, false) => '.',
+            });
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        render_path, search_slopes, stream_tree_counts, tree_count_for_slope, Day3, Extremes, Grid,
+        Slope,
+    };
+    use crate::solution::Solution;
+    use std::io;
+
+    const EXAMPLE: &str = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n\
+                           .#...##..#.\n..#.##.....\n.#.#.#....#\n.#........#\n\
+                           #.##...#...\n#...##....#\n.#..#...#.#\n";
+
+    #[test]
+    fn columns_wrap_around_the_width() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        assert_eq!((map.height(), map.width()), (11, 11));
+        assert!(map.is_tree(0, 2));
+        assert!(map.is_tree(0, 13));
+        assert!(map.is_tree(0, 11 * 1000 + 3));
+        assert!(!map.is_tree(0, 11));
+    }
+
+    #[test]
+    fn tree_count_for_slope_works_on_the_example() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        let counts: Vec<i32> = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2), (30, 1)]
+            .iter()
+            .map(|&(right, down)| tree_count_for_slope(&map, &Slope { right, down }))
+            .collect();
+        assert_eq!(counts, vec![2, 7, 3, 4, 2, 3]);
+    }
+
+    #[test]
+    fn steep_and_vertical_slopes_work() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        let count = |right, down| tree_count_for_slope(&map, &Slope { right, down });
+        assert_eq!(count(0, 1), 3);
+        assert_eq!(count(1, 3), 0);
+        assert_eq!(count(2, 3), 1);
+        assert_eq!(count(1, 11), 0);
+    }
+
+    #[test]
+    fn search_slopes_finds_the_fewest_and_most_trees() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        assert_eq!(
+            search_slopes(&map, 1..=7, 1..=1),
+            Some(Extremes {
+                fewest: (Slope { right: 2, down: 1 }, 1),
+                most: (Slope { right: 3, down: 1 }, 7),
+            })
+        );
+    }
+
+    #[test]
+    fn render_path_marks_each_landing() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        let rendered = render_path(&map, &Slope { right: 3, down: 1 });
+        let rows: Vec<&str> = rendered.lines().collect();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "..##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...X.#");
+        assert_eq!(rendered.matches('X').count(), 7);
+        assert_eq!(rendered.matches('O').count(), 3);
+    }
+
+    #[test]
+    fn huge_slopes_wrap_instead_of_overflowing() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        let huge = Slope {
+            right: usize::MAX,
+            down: 1,
+        };
+        let narrow = Slope {
+            right: usize::MAX % map.width(),
+            down: 1,
+        };
+        assert_eq!(
+            tree_count_for_slope(&map, &huge),
+            tree_count_for_slope(&map, &narrow)
+        );
+        assert_eq!(render_path(&map, &huge), render_path(&map, &narrow));
+        assert_eq!(
+            stream_tree_counts(EXAMPLE.as_bytes(), &[huge]).unwrap(),
+            vec![tree_count_for_slope(&map, &narrow)]
+        );
+
+        let steep = Slope {
+            right: 1,
+            down: usize::MAX,
+        };
+        assert_eq!(tree_count_for_slope(&map, &steep), 0);
+    }
+
+    #[test]
+    fn rows_wider_than_a_word_keep_every_square() {
+        let row = format!("{}#{}#", ".".repeat(63), ".".repeat(70));
+        let map = Grid::new(vec![row.as_str(), &row.replace('#', ".")]);
+        assert_eq!(map.width(), 135);
+        let trees: Vec<usize> = (0..135).filter(|&col| map.is_tree(0, col)).collect();
+        assert_eq!(trees, vec![63, 134]);
+        assert!(map.is_tree(0, 135 + 63));
+        assert!((0..135).all(|col| !map.is_tree(1, col)));
+    }
+
+    #[test]
+    fn stream_tree_counts_matches_the_parsed_map() {
+        let map = Day3.parse(EXAMPLE).unwrap();
+        let slopes: Vec<Slope> = [(1, 1), (3, 1), (0, 1), (1, 2), (2, 3), (40, 4)]
+            .iter()
+            .map(|&(right, down)| Slope { right, down })
+            .collect();
+        let expected: Vec<i32> = slopes
+            .iter()
+            .map(|slope| tree_count_for_slope(&map, slope))
+            .collect();
+        assert_eq!(
+            stream_tree_counts(EXAMPLE.as_bytes(), &slopes).unwrap(),
+            expected
+        );
+
+        let err = stream_tree_counts("..#\r\n.#.\r\n.#\n".as_bytes(), &slopes).unwrap_err();
+        assert!(err.to_string().starts_with("day 3, line 3, column 1"));
+    }
+
+    #[test]
+    fn stream_tree_counts_rejects_an_empty_first_row() {
+        let slopes = [Slope { right: 3, down: 1 }];
+        let err = stream_tree_counts("\n\n.#\n".as_bytes(), &slopes).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("day 3, line 1, column 1"));
+        assert!(err.to_string().ends_with("expected a map"));
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        let err = Day3.parse("..#\n.#\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert!(Day3.parse("").is_err());
+    }
 }
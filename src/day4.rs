@@ -1,137 +1,772 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    records::{self, Record},
+    solution::{Answer, Solution},
+};
 
-const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
-const REQUIRED_FIELDS: [&str; 7] = ["byr", "ecl", "eyr", "hcl", "hgt", "iyr", "pid"];
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt, fs,
+    io::{self, Write},
+    path::Path,
+};
 
-pub fn execute() {
-    part_one();
-    part_two();
+const CENTIMETRES_PER_INCH: f64 = 2.54;
+
+const DEFAULT_SCHEMA: &str = include_str!("../schemas/passport.toml");
+
+#[derive(Debug, Deserialize)]
+struct SchemaFile {
+    #[serde(default)]
+    fields: Vec<RawField>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawField {
+    name: String,
+    #[serde(default = "required_by_default")]
+    required: bool,
+    rule: Option<RawRule>,
+}
+
+fn required_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum RawRule {
+    Number {
+        min: i64,
+        max: i64,
+        digits: Option<usize>,
+    },
+    Pattern {
+        pattern: String,
+    },
+    OneOf {
+        values: Vec<String>,
+    },
+    Measure {
+        units: BTreeMap<String, Bounds>,
+    },
 }
 
-fn part_one() {
-    let mut valid_passports = 0;
-    for passport in get_passports() {
-        let fields = parse_fields(&passport);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Bounds {
+    pub min: i64,
+    pub max: i64,
+}
 
-        if fields.len() == 7 {
-            valid_passports += 1;
+impl Bounds {
+    pub fn contains(&self, num: i64) -> bool {
+        (self.min..=self.max).contains(&num)
+    }
+}
+
+/// What a field's value has to look like.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// Anything, as long as the field is there.
+    Any,
+    /// A whole number in range, optionally written with exactly `digits` digits.
+    Number {
+        bounds: Bounds,
+        digits: Option<usize>,
+    },
+    /// A regex matching the whole value.
+    Pattern(Regex),
+    OneOf(Vec<String>),
+    /// A number followed by one of the units, each with its own range.
+    Measure(BTreeMap<String, Bounds>),
+}
+
+impl Rule {
+    pub fn check(&self, value: &str) -> bool {
+        match self {
+            Rule::Any => true,
+            Rule::Number { bounds, digits } => {
+                digits.is_none_or(|digits| value.len() == digits)
+                    && value.bytes().all(|byte| byte.is_ascii_digit())
+                    && value.parse().is_ok_and(|num| bounds.contains(num))
+            }
+            Rule::Pattern(regex) => regex.is_match(value),
+            Rule::OneOf(values) => values.iter().any(|allowed| allowed == value),
+            Rule::Measure(units) => {
+                let split = value
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(value.len());
+                let (num, unit) = value.split_at(split);
+                match (num.parse(), units.get(unit)) {
+                    (Ok(num), Some(bounds)) => bounds.contains(num),
+                    _ => false,
+                }
+            }
         }
     }
-    print!("Day 4 - A: {:?}", valid_passports);
 }
 
-fn part_two() {
-    let mut valid_passports = 0;
-    for passport in get_passports() {
-        let fields = parse_fields(&passport);
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Any => write!(f, "any value"),
+            Rule::Number { bounds, digits } => {
+                write!(f, "number {}-{}", bounds.min, bounds.max)?;
+                match digits {
+                    Some(digits) => write!(f, " ({} digits)", digits),
+                    None => Ok(()),
+                }
+            }
+            Rule::Pattern(regex) => write!(f, "pattern `{}`", regex.as_str()),
+            Rule::OneOf(values) => write!(f, "one of {}", values.join(", ")),
+            Rule::Measure(units) => {
+                let units: Vec<String> = units
+                    .iter()
+                    .map(|(unit, bounds)| format!("{}-{}{}", bounds.min, bounds.max, unit))
+                    .collect();
+                write!(f, "measure {}", units.join(" or "))
+            }
+        }
+    }
+}
+
+/// Something wrong with one field of a passport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "problem", rename_all = "kebab-case")]
+pub enum Problem {
+    Missing {
+        field: String,
+    },
+    Duplicated {
+        field: String,
+        count: usize,
+    },
+    Unknown {
+        field: String,
+    },
+    Invalid {
+        field: String,
+        value: String,
+        rule: String,
+    },
+}
 
-        if fields_are_valid(&fields) {
-            valid_passports += 1;
+impl Problem {
+    pub fn field(&self) -> &str {
+        match self {
+            Problem::Missing { field }
+            | Problem::Duplicated { field, .. }
+            | Problem::Unknown { field }
+            | Problem::Invalid { field, .. } => field,
         }
     }
-    println!(", B: {:?}", valid_passports);
+
+    /// Whether the problem is with which fields are there rather than their values.
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Problem::Missing { .. } | Problem::Duplicated { .. })
+    }
 }
 
-fn fields_are_valid(fields: &Vec<Vec<&str>>) -> bool {
-    let mut names: Vec<&str> = fields.iter().map(|f| f[0]).collect();
-    names.sort();
-    if names != REQUIRED_FIELDS {
-        return false;
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Missing { .. } => write!(f, "missing"),
+            Problem::Duplicated { count, .. } => write!(f, "given {} times", count),
+            Problem::Unknown { .. } => write!(f, "not in the schema"),
+            Problem::Invalid { value, rule, .. } => write!(f, "`{}` fails {}", value, rule),
+        }
     }
+}
 
-    for field in fields {
-        let name = field[0];
-        let value = field[1];
+/// Every problem found with one passport, numbered from 1 in input order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnosis {
+    pub passport: usize,
+    pub problems: Vec<Problem>,
+}
 
-        match name {
-            "byr" => {
-                // byr (Birth Year) - four digits; at least 1920 and at most 2002.
-                let year = value.parse::<i32>().expect("must me a number");
-                if year < 1920 || year > 2002 {
-                    return false;
-                }
-            }
-            "iyr" => {
-                // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
-                let year = value.parse::<i32>().expect("must me a number");
-                if year < 2010 || year > 2020 {
-                    return false;
-                }
+impl Diagnosis {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldSpec {
+    pub name: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// The fields a passport (or any other document) may have and the rules their
+/// values must follow. See `schemas/passport.toml` for the file format.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    pub fields: Vec<FieldSpec>,
+}
+
+impl Default for Schema {
+    /// The rules from the puzzle, as written in `schemas/passport.toml`.
+    fn default() -> Self {
+        Schema::from_toml(DEFAULT_SCHEMA).expect("the built-in passport schema is valid")
+    }
+}
+
+impl Schema {
+    pub fn load(path: &Path) -> Result<Schema, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        Schema::from_toml(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Schema, String> {
+        let file: SchemaFile = toml::from_str(contents).map_err(|err| err.to_string())?;
+        let mut fields: Vec<FieldSpec> = Vec::with_capacity(file.fields.len());
+        for raw in file.fields {
+            if fields.iter().any(|field| field.name == raw.name) {
+                return Err(format!("field `{}` is listed twice", raw.name));
             }
-            "eyr" => {
-                // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
-                let year = value.parse::<i32>().expect("must me a number");
-                if year < 2020 || year > 2030 {
-                    return false;
+            let name = raw.name;
+            let rule = match raw.rule {
+                None => Rule::Any,
+                Some(RawRule::Number { min, max, digits }) => Rule::Number {
+                    bounds: Bounds { min, max },
+                    digits,
+                },
+                Some(RawRule::Pattern { pattern }) => {
+                    let regex = Regex::new(&format!("^(?:{})$", pattern))
+                        .map_err(|err| format!("field `{}` has a bad pattern: {}", name, err))?;
+                    Rule::Pattern(regex)
                 }
+                Some(RawRule::OneOf { values }) => Rule::OneOf(values),
+                Some(RawRule::Measure { units }) => Rule::Measure(units),
+            };
+            fields.push(FieldSpec {
+                name,
+                required: raw.required,
+                rule,
+            });
+        }
+        Ok(Schema { fields })
+    }
+
+    pub fn spec(&self, name: &str) -> Option<&FieldSpec> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Lists every problem with a passport's fields: the schema's fields in
+    /// order, each missing, duplicated or with invalid values, then any fields
+    /// the schema doesn't know about.
+    pub fn diagnose(&self, passport: &Record) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        for spec in &self.fields {
+            let values: Vec<&str> = passport.get_all(&spec.name).collect();
+            if values.is_empty() && spec.required {
+                problems.push(Problem::Missing {
+                    field: spec.name.clone(),
+                });
             }
-            "ecl" => {
-                // ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
-                if !EYE_COLORS.contains(&value) {
-                    return false;
-                }
+            if values.len() > 1 {
+                problems.push(Problem::Duplicated {
+                    field: spec.name.clone(),
+                    count: values.len(),
+                });
             }
-            "pid" => {
-                // pid (Passport ID) - a nine-digit number, including leading zeroes.
-                if value.len() != 9 || !value.chars().all(|ch| ch.is_digit(10)) {
-                    return false;
-                }
+            for value in values.into_iter().filter(|value| !spec.rule.check(value)) {
+                problems.push(Problem::Invalid {
+                    field: spec.name.clone(),
+                    value: value.to_string(),
+                    rule: spec.rule.to_string(),
+                });
             }
-            "hcl" => {
-                // hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
-                let mut chars = value.chars();
-                if value.len() != 7
-                    || chars.next().unwrap() != '#'
-                    || !chars.all(|ch| ch.is_digit(16))
-                {
-                    return false;
-                }
+        }
+
+        for name in passport.keys() {
+            if self.spec(name).is_some() {
+                continue;
             }
-            // hgt (Height) - a number followed by either cm or in:
-            //     If cm, the number must be at least 150 and at most 193.
-            //     If in, the number must be at least 59 and at most 76.
-            "hgt" => {
-                let index = value.len() - 2;
-                let measurement = &value[index..];
-                match &value[..index].parse::<i32>() {
-                    Ok(height) => match measurement {
-                        "cm" => {
-                            if *height < 150 || *height > 193 {
-                                return false;
-                            }
-                        }
-                        "in" => {
-                            if *height < 59 || *height > 76 {
-                                return false;
-                            }
-                        }
-                        _ => {
-                            return false;
-                        }
-                    },
-                    Err(_) => {
-                        return false;
-                    }
-                }
+            let count = passport.get_all(name).count();
+            problems.push(Problem::Unknown {
+                field: name.to_string(),
+            });
+            if count > 1 {
+                problems.push(Problem::Duplicated {
+                    field: name.to_string(),
+                    count,
+                });
             }
-            _ => {}
         }
+        problems
+    }
+
+    /// Whether every required field is there exactly once, whatever its value.
+    pub fn is_complete(&self, passport: &Record) -> bool {
+        self.diagnose(passport)
+            .iter()
+            .all(|problem| !problem.is_structural())
+    }
+
+    /// Whether the passport has no problems at all.
+    pub fn is_valid(&self, passport: &Record) -> bool {
+        self.diagnose(passport).is_empty()
     }
-    true
 }
 
-fn get_passports() -> Vec<String> {
-    fs::read_to_string("data/day4.txt")
-        .unwrap()
-        .split("\n\n")
-        .map(String::from)
+pub fn diagnose(schema: &Schema, passports: &[Record]) -> Vec<Diagnosis> {
+    passports
+        .iter()
+        .enumerate()
+        .map(|(i, passport)| Diagnosis {
+            passport: i + 1,
+            problems: schema.diagnose(passport),
+        })
         .collect()
 }
 
-fn parse_fields<'a>(passport: &'a String) -> Vec<Vec<&'a str>> {
-    passport
-        .split(&['\n', ' '][..])
-        .filter(|&f| !f.starts_with("cid") && f != "")
-        .map(|f| f.split(":").collect::<Vec<&str>>())
+/// Prints one row per problem, then how many passports had none.
+pub fn print_table(diagnoses: &[Diagnosis]) {
+    println!("{:>8}  {:<8}  problem", "passport", "field");
+    for diagnosis in diagnoses {
+        for problem in &diagnosis.problems {
+            println!(
+                "{:>8}  {:<8}  {}",
+                diagnosis.passport,
+                problem.field(),
+                problem
+            );
+        }
+    }
+    let valid = diagnoses
+        .iter()
+        .filter(|diagnosis| diagnosis.is_valid())
+        .count();
+    println!("{} of {} passports valid", valid, diagnoses.len());
+}
+
+pub fn print_json(diagnoses: &[Diagnosis]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(diagnoses).map_err(|err| err.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// A passport's fields converted to proper types, with the height in
+/// centimetres whichever unit it was given in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Passport {
+    pub birth_year: i32,
+    pub issue_year: i32,
+    pub expiration_year: i32,
+    pub height_cm: f64,
+    pub hair_color: String,
+    pub eye_color: String,
+    pub passport_id: String,
+    pub country_id: Option<String>,
+}
+
+/// Why a record couldn't be turned into a [`Passport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl TryFrom<&Record> for Passport {
+    type Error = ConversionError;
+
+    fn try_from(record: &Record) -> Result<Passport, ConversionError> {
+        let field = |name: &str| {
+            record.get(name).ok_or_else(|| ConversionError {
+                field: name.to_string(),
+                message: "is missing".to_string(),
+            })
+        };
+        let year = |name: &str| {
+            let value = field(name)?;
+            value.parse().map_err(|_| ConversionError {
+                field: name.to_string(),
+                message: format!("`{}` is not a year", value),
+            })
+        };
+
+        let birth_year = year("byr")?;
+        let issue_year = year("iyr")?;
+        let expiration_year = year("eyr")?;
+        let height = field("hgt")?;
+        Ok(Passport {
+            birth_year,
+            issue_year,
+            expiration_year,
+            height_cm: centimetres(height).ok_or_else(|| ConversionError {
+                field: "hgt".to_string(),
+                message: format!("`{}` is not a height in cm or in", height),
+            })?,
+            hair_color: field("hcl")?.to_string(),
+            eye_color: field("ecl")?.to_string(),
+            passport_id: field("pid")?.to_string(),
+            country_id: record.get("cid").map(str::to_string),
+        })
+    }
+}
+
+fn centimetres(height: &str) -> Option<f64> {
+    let cm = if let Some(cm) = height.strip_suffix("cm") {
+        decimal(cm)?
+    } else {
+        let inches = decimal(height.strip_suffix("in")?)?;
+        // Rounded to the hundredth, finer than any inch value resolves, so
+        // 66in exports as 167.64 rather than carrying the float's error.
+        (inches * CENTIMETRES_PER_INCH * 100.0).round() / 100.0
+    };
+    Some(cm).filter(|cm| cm.is_finite())
+}
+
+/// Parses digits with an optional decimal part, like `Rule::Measure` but allowing
+/// fractions; `f64`'s own parser would also take `NaN`, `inf` and `1e2`.
+fn decimal(text: &str) -> Option<f64> {
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, "0"));
+    if digits(whole) && digits(fraction) {
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Converts the passports the schema accepts, numbering them from 1 in input
+/// order. Passports that fail the schema are left out; ones that pass it but
+/// still can't be converted (with a looser schema) come back as errors.
+pub fn valid_passports(
+    schema: &Schema,
+    records: &[Record],
+) -> Vec<(usize, Result<Passport, ConversionError>)> {
+    records
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| schema.is_valid(record))
+        .map(|(i, record)| (i + 1, Passport::try_from(record)))
         .collect()
 }
+
+pub fn write_csv(passports: &[Passport], out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "birth_year,issue_year,expiration_year,height_cm,hair_color,eye_color,passport_id,country_id"
+    )?;
+    for passport in passports {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            passport.birth_year,
+            passport.issue_year,
+            passport.expiration_year,
+            passport.height_cm,
+            csv_field(&passport.hair_color),
+            csv_field(&passport.eye_color),
+            csv_field(&passport.passport_id),
+            csv_field(passport.country_id.as_deref().unwrap_or_default()),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn write_json(passports: &[Passport], out: &mut impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, passports)?;
+    writeln!(out)
+}
+
+/// Quotes a CSV field if it has anything that would otherwise break the row.
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Default)]
+pub struct Day4 {
+    pub schema: Schema,
+}
+
+impl Solution for Day4 {
+    type Input = Vec<Record>;
+
+    const DAY: u8 = 4;
+
+    fn parse(&self, input: &str) -> Result<Vec<Record>, ParseError> {
+        records::parse_records(&Context::new(Self::DAY, input), input)
+    }
+
+    fn part_one(&self, passports: &Vec<Record>) -> Answer {
+        let valid_passports = passports
+            .iter()
+            .filter(|passport| self.schema.is_complete(passport))
+            .count();
+        valid_passports.into()
+    }
+
+    fn part_two(&self, passports: &Vec<Record>) -> Answer {
+        let valid_passports = passports
+            .iter()
+            .filter(|passport| self.schema.is_valid(passport))
+            .count();
+        valid_passports.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        valid_passports, write_csv, write_json, ConversionError, Day4, Passport, Problem, Schema,
+    };
+    use crate::{
+        error::Context,
+        records::{self, Record},
+        solution::{Answer, Solution},
+    };
+    use std::convert::TryFrom;
+
+    fn fields(passport: &str) -> Record {
+        records::parse_record(&Context::new(4, passport), passport).unwrap()
+    }
+
+    #[test]
+    fn default_schema_follows_the_puzzle_rules() {
+        let schema = Schema::default();
+        let check = |name: &str, value: &str| schema.spec(name).unwrap().rule.check(value);
+        assert!(check("byr", "2002"));
+        assert!(!check("byr", "2003"));
+        assert!(!check("byr", "02002"));
+        assert!(check("hgt", "60in"));
+        assert!(check("hgt", "190cm"));
+        assert!(!check("hgt", "190in"));
+        assert!(!check("hgt", "190"));
+        assert!(check("hcl", "#123abc"));
+        assert!(!check("hcl", "#123abz"));
+        assert!(!check("hcl", "123abc"));
+        assert!(check("ecl", "brn"));
+        assert!(!check("ecl", "wat"));
+        assert!(check("pid", "000000001"));
+        assert!(!check("pid", "0123456789"));
+        assert!(!schema.spec("cid").unwrap().required);
+    }
+
+    #[test]
+    fn diagnose_lists_every_problem() {
+        let schema = Schema::default();
+        let passport =
+            fields("byr:1900 iyr:2015 eyr:2025 eyr:2026 hcl:#abcdef ecl:xyz cid:1 pet:cat pet:dog");
+        let problems = schema.diagnose(&passport);
+        assert_eq!(
+            problems,
+            vec![
+                Problem::Invalid {
+                    field: "byr".into(),
+                    value: "1900".into(),
+                    rule: "number 1920-2002 (4 digits)".into(),
+                },
+                Problem::Duplicated {
+                    field: "eyr".into(),
+                    count: 2,
+                },
+                Problem::Missing {
+                    field: "hgt".into()
+                },
+                Problem::Invalid {
+                    field: "ecl".into(),
+                    value: "xyz".into(),
+                    rule: "one of amb, blu, brn, gry, grn, hzl, oth".into(),
+                },
+                Problem::Missing {
+                    field: "pid".into()
+                },
+                Problem::Unknown {
+                    field: "pet".into()
+                },
+                Problem::Duplicated {
+                    field: "pet".into(),
+                    count: 2,
+                },
+            ]
+        );
+        assert_eq!(
+            problems[0].to_string(),
+            "`1900` fails number 1920-2002 (4 digits)"
+        );
+        assert!(schema.is_valid(&fields(
+            "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:087499704"
+        )));
+    }
+
+    #[test]
+    fn duplicated_fields_make_a_passport_incomplete() {
+        let passports = Day4::default()
+            .parse("ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 iyr:2017")
+            .unwrap();
+        assert_eq!(Day4::default().part_one(&passports), Answer::Number(0));
+    }
+
+    #[test]
+    fn diagnoses_serialize_to_json() {
+        let diagnoses = super::diagnose(&Schema::default(), &[fields("cid:1 hgt:60in")]);
+        let json = serde_json::to_string(&diagnoses).unwrap();
+        assert!(json.starts_with(
+            "[{\"passport\":1,\"problems\":[{\"problem\":\"missing\",\"field\":\"byr\"},"
+        ));
+    }
+
+    #[test]
+    fn passports_normalize_heights_and_years() {
+        let passport = Passport::try_from(&fields(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f",
+        ))
+        .unwrap();
+        assert_eq!(passport.birth_year, 1980);
+        assert_eq!(passport.height_cm, 187.96);
+        assert_eq!(passport.passport_id, "087499704");
+        assert_eq!(passport.country_id, None);
+
+        let err = Passport::try_from(&fields("byr:1980 iyr:20x2")).unwrap_err();
+        assert_eq!(
+            err,
+            ConversionError {
+                field: "iyr".into(),
+                message: "`20x2` is not a year".into(),
+            }
+        );
+        assert_eq!(err.to_string(), "`iyr` `20x2` is not a year");
+    }
+
+    #[test]
+    fn only_valid_passports_are_exported() {
+        let records = vec![
+            fields("byr:1980 iyr:2012 eyr:2030 hgt:150cm hcl:#623a2f ecl:grn pid:087499704 cid:9"),
+            fields("byr:1980 iyr:2012 eyr:2030 hgt:150cm hcl:#623a2f ecl:grn"),
+            fields("byr:2000 iyr:2020 eyr:2020 hgt:59in hcl:#000000 ecl:oth pid:000000001"),
+        ];
+        let passports: Vec<Passport> = valid_passports(&Schema::default(), &records)
+            .into_iter()
+            .map(|(i, passport)| {
+                assert_ne!(i, 2);
+                passport.unwrap()
+            })
+            .collect();
+
+        let mut csv = Vec::new();
+        write_csv(&passports, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], "1980,2012,2030,150,#623a2f,grn,087499704,9");
+        assert_eq!(rows[2], "2000,2020,2020,149.86,#000000,oth,000000001,");
+    }
+
+    #[test]
+    fn heights_must_be_plain_decimal_numbers() {
+        let height = |hgt: &str| {
+            Passport::try_from(&fields(&format!(
+                "byr:1980 iyr:2012 eyr:2030 hgt:{} hcl:#623a2f ecl:grn pid:087499704",
+                hgt
+            )))
+            .map(|passport| passport.height_cm)
+        };
+        assert_eq!(height("150.5cm"), Ok(150.5));
+        assert_eq!(height("60.5in"), Ok(153.67));
+        for bad in &[
+            "NaNcm",
+            "infcm",
+            "infinityin",
+            "1e2cm",
+            "-150cm",
+            "+150cm",
+            ".5cm",
+            "150.cm",
+            "cm",
+        ] {
+            let err = height(bad).unwrap_err();
+            assert_eq!(err.field, "hgt", "{}", bad);
+        }
+        let huge = format!("{}cm", "9".repeat(400));
+        assert!(height(&huge).is_err());
+    }
+
+    #[test]
+    fn converted_heights_export_exactly() {
+        let passports: Vec<Passport> = ["66in", "73in"]
+            .iter()
+            .map(|height| {
+                Passport::try_from(&fields(&format!(
+                    "byr:1980 iyr:2012 eyr:2030 hgt:{} hcl:#623a2f ecl:grn pid:087499704",
+                    height
+                )))
+                .unwrap()
+            })
+            .collect();
+
+        let mut csv = Vec::new();
+        write_csv(&passports, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let heights: Vec<&str> = csv
+            .lines()
+            .skip(1)
+            .map(|row| row.split(',').nth(3).unwrap())
+            .collect();
+        assert_eq!(heights, vec!["167.64", "185.42"]);
+
+        let mut json = Vec::new();
+        write_json(&passports, &mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\"height_cm\": 167.64,"));
+        assert!(json.contains("\"height_cm\": 185.42,"));
+    }
+
+    #[test]
+    fn schemas_load_from_toml() {
+        let schema = Schema::from_toml(
+            "[[fields]]\nname = \"id\"\nrule = { kind = \"pattern\", pattern = \"[A-Z]{2}[0-9]+\" }\n\
+             [[fields]]\nname = \"weight\"\nrequired = false\n\
+             rule = { kind = \"measure\", units = { kg = { min = 1, max = 200 } } }\n",
+        )
+        .unwrap();
+        assert!(schema.is_valid(&fields("id:AB12")));
+        assert!(schema.is_valid(&fields("id:AB12 weight:80kg")));
+        assert!(!schema.is_valid(&fields("id:AB12 weight:80lb")));
+        assert!(!schema.is_valid(&fields("id:ab12")));
+        assert!(!schema.is_valid(&fields("weight:80kg")));
+
+        let twice = "[[fields]]\nname = \"id\"\n[[fields]]\nname = \"id\"\n";
+        assert!(Schema::from_toml(twice).unwrap_err().contains("twice"));
+        let bad = "[[fields]]\nname = \"id\"\nrule = { kind = \"pattern\", pattern = \"(\" }\n";
+        assert!(Schema::from_toml(bad).unwrap_err().contains("bad pattern"));
+        let unknown = "[[fields]]\nname = \"id\"\nrule = { kind = \"date\" }\n";
+        assert!(Schema::from_toml(unknown).is_err());
+    }
+
+    #[test]
+    fn parse_reports_fields_without_values() {
+        let err = Day4::default()
+            .parse("ecl:gry pid:1\nhcl\n\nbyr:1937")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+
+    #[test]
+    fn bad_years_and_heights_are_invalid_not_fatal() {
+        let passports = Day4::default()
+            .parse("byr:19x7 iyr:2017 eyr:2020 hgt:1 hcl:#fffffd ecl:gry pid:860033327")
+            .unwrap();
+        assert_eq!(Day4::default().part_one(&passports), Answer::Number(1));
+        assert_eq!(Day4::default().part_two(&passports), Answer::Number(0));
+    }
+}
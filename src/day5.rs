@@ -1,78 +1,321 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    seating::SeatingChart,
+    solution::{Answer, Solution},
+};
+use std::{fmt, ops::Range};
 
-pub fn execute() {
-    part_one();
-    part_two();
+/// The shape of the plane. Both dimensions are powers of two, so a pass is a
+/// binary number: one bit per halving of the rows (`B` = 1, `F` = 0) followed
+/// by one per halving of the columns (`R` = 1, `L` = 0). `Geometry::new` keeps
+/// the two bit counts at 32 or fewer in total, so every seat id fits in a `u32`
+/// and shifting by either count never overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    rows: u32,
+    columns: u32,
 }
 
-fn part_one() {
-    let highest_id = get_boarding_passes()
-        .iter()
-        .map(calculate_seat_id)
-        .max()
-        .unwrap();
-    print!("Day 5 - A: {:?}", highest_id);
+impl Geometry {
+    /// The puzzle's plane: 128 rows of 8 seats, with 10 character passes.
+    pub const PUZZLE: Geometry = Geometry {
+        rows: 128,
+        columns: 8,
+    };
+
+    pub fn new(rows: u32, columns: u32) -> Result<Geometry, String> {
+        if !rows.is_power_of_two() || !columns.is_power_of_two() {
+            return Err(format!(
+                "a plane of {} rows by {} columns isn't a power of two in both directions",
+                rows, columns
+            ));
+        }
+        if rows.trailing_zeros() + columns.trailing_zeros() > u32::BITS {
+            return Err(format!(
+                "a plane of {} rows by {} columns has too many seats to number",
+                rows, columns
+            ));
+        }
+        Ok(Geometry { rows, columns })
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn row_bits(&self) -> usize {
+        self.rows.trailing_zeros() as usize
+    }
+
+    pub fn column_bits(&self) -> usize {
+        self.columns.trailing_zeros() as usize
+    }
+
+    /// The length of a boarding pass for this plane.
+    pub fn pass_len(&self) -> usize {
+        self.row_bits() + self.column_bits()
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry::PUZZLE
+    }
+}
+
+/// Why a boarding pass couldn't be decoded: `at` is the byte range of the
+/// offending part of the pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub at: Range<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.at.start + 1)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A seat on a plane, which round-trips to the boarding pass that names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardingPass {
+    pub row: u32,
+    pub column: u32,
+    geometry: Geometry,
 }
 
-fn part_two() {
-    let mut ids: Vec<i32> = get_boarding_passes()
-        .iter()
-        .map(calculate_seat_id)
-        .collect();
-    ids.sort();
-
-    for (i, id) in ids.iter().enumerate() {
-        if id + 1 != ids[i + 1] {
-            println!(", B: {:?}", id + 1);
-            break;
+impl BoardingPass {
+    pub fn new(row: u32, column: u32, geometry: Geometry) -> Result<BoardingPass, String> {
+        if row >= geometry.rows || column >= geometry.columns {
+            return Err(format!(
+                "seat {}, {} is outside a plane of {} rows by {} columns",
+                row, column, geometry.rows, geometry.columns
+            ));
+        }
+        Ok(BoardingPass {
+            row,
+            column,
+            geometry,
+        })
+    }
+
+    /// The seat with the given id, if the plane has one.
+    pub fn from_id(id: u32, geometry: Geometry) -> Option<BoardingPass> {
+        let column = id & (geometry.columns - 1);
+        let row = id >> geometry.column_bits();
+        BoardingPass::new(row, column, geometry).ok()
+    }
+
+    pub fn decode(pass: &str, geometry: Geometry) -> Result<BoardingPass, DecodeError> {
+        if pass.chars().count() != geometry.pass_len() {
+            return Err(DecodeError {
+                at: 0..pass.len(),
+                message: format!("expected {} characters", geometry.pass_len()),
+            });
         }
+        let split = pass
+            .char_indices()
+            .nth(geometry.row_bits())
+            .map_or(pass.len(), |(i, _)| i);
+        let (rows, columns) = pass.split_at(split);
+        Ok(BoardingPass {
+            row: decode_bits(rows, ('F', 'B'), 0)?,
+            column: decode_bits(columns, ('L', 'R'), rows.len())?,
+            geometry,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        let mut pass = encode_bits(self.row, self.geometry.row_bits(), ('F', 'B'));
+        pass.push_str(&encode_bits(
+            self.column,
+            self.geometry.column_bits(),
+            ('L', 'R'),
+        ));
+        pass
+    }
+
+    /// `row * columns + column`, which is also the pass read as a binary number.
+    pub fn id(&self) -> u32 {
+        self.row << self.geometry.column_bits() | self.column
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
     }
 }
 
-fn calculate_seat_id(pass: &String) -> i32 {
-    let mut rows_partition = 128;
-    let mut seats_partition = 8;
-    let mut rows = 0..128;
-    let mut seats = 0..8;
-
-    for ch in pass.chars() {
-        match ch {
-            'F' => {
-                rows_partition /= 2;
-                rows.end -= rows_partition;
-            }
-            'B' => {
-                rows_partition /= 2;
-                rows.start += rows_partition;
-            }
-            'L' => {
-                seats_partition /= 2;
-                seats.end -= seats_partition;
-            }
-            'R' => {
-                seats_partition /= 2;
-                seats.start += seats_partition;
+impl fmt::Display for BoardingPass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+/// Reads `text` as a binary number written with `zero` and `one`.
+fn decode_bits(text: &str, (zero, one): (char, char), offset: usize) -> Result<u32, DecodeError> {
+    let mut num: u32 = 0;
+    for (i, ch) in text.char_indices() {
+        let bit = match ch {
+            _ if ch == zero => 0,
+            _ if ch == one => 1,
+            _ => {
+                return Err(DecodeError {
+                    at: offset + i..offset + i + ch.len_utf8(),
+                    message: format!("expected `{}` or `{}`", zero, one),
+                })
             }
-            _ => panic!("Invalid character"),
-        }
+        };
+        num = num << 1 | bit;
     }
-    rows.start * 8 + seats.start
+    Ok(num)
 }
 
-fn get_boarding_passes() -> Vec<String> {
-    fs::read_to_string("data/day5.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
+fn encode_bits(num: u32, bits: usize, (zero, one): (char, char)) -> String {
+    (0..bits)
+        .rev()
+        .map(|bit| if num >> bit & 1 == 1 { one } else { zero })
         .collect()
 }
 
+#[derive(Default)]
+pub struct Day5 {
+    pub geometry: Geometry,
+}
+
+impl Solution for Day5 {
+    type Input = Vec<BoardingPass>;
+
+    const DAY: u8 = 5;
+
+    fn parse(&self, input: &str) -> Result<Vec<BoardingPass>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input
+            .lines()
+            .map(|pass| {
+                BoardingPass::decode(pass, self.geometry)
+                    .map_err(|err| ctx.error(&pass[err.at], err.message))
+            })
+            .collect()
+    }
+
+    fn part_one(&self, passes: &Vec<BoardingPass>) -> Answer {
+        passes.iter().map(BoardingPass::id).max().into()
+    }
+
+    /// Our seat is the gap between the first and last taken seats; real inputs
+    /// have exactly one.
+    fn part_two(&self, passes: &Vec<BoardingPass>) -> Answer {
+        let chart = SeatingChart::new(self.geometry, passes);
+        chart.vacancies().gaps.first().map(BoardingPass::id).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{BoardingPass, Day5, Geometry};
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn part_two_finds_the_gap_without_panicking_when_there_is_none() {
+        let day = Day5::default();
+        let passes = day.parse("FFFFFFFLLL\nFFFFFFFLLR\nFFFFFFFLRR").unwrap();
+        assert_eq!(day.part_two(&passes), Answer::Number(2));
+        let passes = day.parse("FFFFFFFLLL\nFFFFFFFLLR").unwrap();
+        assert_eq!(day.part_two(&passes), Answer::None);
+        assert_eq!(day.part_two(&Vec::new()), Answer::None);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_passes() {
+        let err = Day5::default()
+            .parse("BFFFBBFRRR\nBFFFBLFRRR")
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 6, "L"));
+        assert!(Day5::default().parse("BFFFBBFRR").is_err());
+        let err = Day5::default().parse("BFFFBBFRRÉ").err().unwrap();
+        assert_eq!(err.text, "É");
+    }
+
+    #[test]
+    fn decode_works() {
+        let seat = |pass| {
+            let pass = BoardingPass::decode(pass, Geometry::PUZZLE).unwrap();
+            (pass.row, pass.column, pass.id())
+        };
+        assert_eq!(seat("FBFBBFFRLR"), (44, 5, 357));
+        assert_eq!(seat("BFFFBBFRRR"), (70, 7, 567));
+        assert_eq!(seat("FFFBBBFRRR"), (14, 7, 119));
+        assert_eq!(seat("BBFFBBFRLL"), (102, 4, 820));
+    }
+
+    #[test]
+    fn encode_works() {
+        let pass = BoardingPass::new(44, 5, Geometry::PUZZLE).unwrap();
+        assert_eq!(pass.encode(), "FBFBBFFRLR");
+        assert!(BoardingPass::new(128, 0, Geometry::PUZZLE).is_err());
+        assert!(BoardingPass::new(0, 8, Geometry::PUZZLE).is_err());
+    }
+
+    #[test]
+    fn geometry_must_be_powers_of_two() {
+        assert!(Geometry::new(100, 8).is_err());
+        assert!(Geometry::new(128, 0).is_err());
+        assert!(Geometry::new(1 << 31, 1 << 2).is_err());
+        assert_eq!(Geometry::new(64, 4).unwrap().pass_len(), 8);
+        assert_eq!(Geometry::new(1, 1).unwrap().pass_len(), 0);
+    }
+
+    #[test]
+    fn every_seat_round_trips_through_its_pass_and_id() {
+        for &(rows, columns) in [(128, 8), (16, 32), (1, 4), (8, 1), (1, 1)].iter() {
+            let geometry = Geometry::new(rows, columns).unwrap();
+            for row in 0..rows {
+                for column in 0..columns {
+                    let pass = BoardingPass::new(row, column, geometry).unwrap();
+                    let code = pass.encode();
+                    assert_eq!(code.len(), geometry.pass_len());
+                    assert_eq!(BoardingPass::decode(&code, geometry), Ok(pass));
+                    assert_eq!(pass.id(), row * columns + column);
+                    assert_eq!(BoardingPass::from_id(pass.id(), geometry), Some(pass));
+                }
+            }
+            assert_eq!(BoardingPass::from_id(rows * columns, geometry), None);
+        }
+    }
+
+    #[test]
+    fn every_pass_round_trips_through_its_seat() {
+        let geometry = Geometry::PUZZLE;
+        for id in 0..1 << geometry.pass_len() {
+            let code: String = (0..geometry.pass_len())
+                .rev()
+                .enumerate()
+                .map(|(i, bit)| match (i < geometry.row_bits(), id >> bit & 1) {
+                    (true, 0) => 'F',
+                    (true, _) => 'B',
+                    (false, 0) => 'L',
+                    (false, _) => 'R',
+                })
+                .collect();
+            let pass = BoardingPass::decode(&code, geometry).unwrap();
+            assert_eq!(pass.id(), id);
+            assert_eq!(pass.encode(), code);
+        }
+    }
+
     #[test]
-    fn calculate_seat_id_works() {
-        assert_eq!(super::calculate_seat_id(&"BFFFBBFRRR".to_string()), 567);
-        assert_eq!(super::calculate_seat_id(&"FFFBBBFRRR".to_string()), 119);
-        assert_eq!(super::calculate_seat_id(&"BBFFBBFRLL".to_string()), 820);
+    fn the_widest_plane_numbers_every_seat() {
+        let geometry = Geometry::new(1 << 16, 1 << 16).unwrap();
+        let pass = BoardingPass::new(u16::MAX as u32, 1, geometry).unwrap();
+        assert_eq!(pass.id(), u32::MAX - (1 << 16) + 2);
+        assert_eq!(BoardingPass::decode(&pass.encode(), geometry), Ok(pass));
     }
 }
@@ -1,33 +1,54 @@
-use std::{collections::HashMap, fs};
+use crate::{
+    error::{Context, ParseError},
+    records,
+    solution::{Answer, Solution},
+};
+use std::collections::HashMap;
 
-pub fn execute() {
-    part_one();
-    part_two();
-}
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<String>;
+
+    const DAY: u8 = 6;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        records::blocks(input)
+            .map(|group| {
+                let answers: Vec<&str> = records::lines(group).collect();
+                for person in &answers {
+                    ctx.expect_chars(person, "abcdefghijklmnopqrstuvwxyz")?;
+                }
+                Ok(answers.join("\n"))
+            })
+            .collect()
+    }
 
-fn part_one() {
-    let mut count = 0;
-    for group in get_groups() {
-        let mut questions_answered = HashMap::new();
-        for answers in group.lines() {
-            for answer in answers.chars() {
-                questions_answered.insert(answer, 1);
+    fn part_one(&self, groups: &Vec<String>) -> Answer {
+        let mut count = 0;
+        for group in groups {
+            let mut questions_answered = HashMap::new();
+            for answers in group.lines() {
+                for answer in answers.chars() {
+                    questions_answered.insert(answer, 1);
+                }
             }
+            count += questions_answered.values().sum::<i32>();
         }
-        count += questions_answered.values().sum::<i32>();
+        count.into()
     }
-    print!("Day 6 - A: {:?}", count);
-}
 
-fn part_two() {
-    let mut total_count = 0;
-    for group in get_groups() {
-        total_count += common_answers_for_group(group);
+    fn part_two(&self, groups: &Vec<String>) -> Answer {
+        let mut total_count = 0;
+        for group in groups {
+            total_count += common_answers_for_group(group);
+        }
+        total_count.into()
     }
-    println!(", B: {:?}", total_count);
 }
 
-fn common_answers_for_group(group: String) -> i32 {
+fn common_answers_for_group(group: &str) -> i32 {
     let mut total_count = 0;
     let mut questions_answered = HashMap::new();
     let answers = group.lines().map(String::from).collect::<Vec<String>>();
@@ -45,14 +66,6 @@ fn common_answers_for_group(group: String) -> i32 {
     total_count
 }
 
-fn get_groups() -> Vec<String> {
-    fs::read_to_string("data/day6.txt")
-        .unwrap()
-        .split("\n\n")
-        .map(String::from)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::common_answers_for_group;
@@ -60,6 +73,6 @@ mod tests {
     #[test]
     fn common_answers_for_group_works() {
         let group = "abcdefg\nxxdexxxx\nnnnntmqred".to_string();
-        assert_eq!(common_answers_for_group(group), 2);
+        assert_eq!(common_answers_for_group(&group), 2);
     }
 }
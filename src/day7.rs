@@ -1,119 +1,164 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
 };
+use std::collections::{HashMap, HashSet};
 
 type BagMap = HashMap<String, Vec<Bag>>;
 
-struct Bag {
-    count: i32,
-    bag_type: String,
+#[derive(Debug, Clone)]
+pub struct Bag {
+    pub count: i32,
+    pub bag_type: String,
 }
 
-pub fn execute() {
-    part_one();
-    part_two();
+/// One line of the luggage rules: a bag and the bags it must contain.
+#[derive(Debug)]
+pub struct Rule {
+    pub parent: String,
+    pub children: Vec<Bag>,
 }
 
-fn part_one() {
-    let mut bag_map: BagMap = HashMap::new();
-
-    for rule in get_rules() {
-        let parent_children: Vec<&str> = rule.split(" contain ").collect();
-        let parent = parse_bag_type(parent_children[0], 0);
-        let children: Vec<Bag> = parent_children[1].split(",").map(parse_bag).collect();
-
-        for child in children {
-            match bag_map.get_mut(&child.bag_type) {
-                Some(c) => c.push(Bag {
-                    bag_type: parent.to_owned(),
-                    count: 0,
-                }),
-                None => {
-                    bag_map.insert(
-                        child.bag_type,
-                        vec![Bag {
-                            bag_type: parent.to_owned(),
-                            count: 0,
-                        }],
-                    );
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<Rule>;
+
+    const DAY: u8 = 7;
+
+    fn parse(&self, input: &str) -> Result<Vec<Rule>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input.lines().map(|line| parse_rule(&ctx, line)).collect()
+    }
+
+    fn part_one(&self, rules: &Vec<Rule>) -> Answer {
+        let mut bag_map: BagMap = HashMap::new();
+
+        for rule in rules {
+            let parent = &rule.parent;
+            for child in &rule.children {
+                match bag_map.get_mut(&child.bag_type) {
+                    Some(c) => c.push(Bag {
+                        bag_type: parent.to_owned(),
+                        count: 0,
+                    }),
+                    None => {
+                        bag_map.insert(
+                            child.bag_type.to_owned(),
+                            vec![Bag {
+                                bag_type: parent.to_owned(),
+                                count: 0,
+                            }],
+                        );
+                    }
                 }
             }
         }
-    }
 
-    let mut parents: HashSet<&str> = HashSet::new();
-    find_distinct_parents("shiny gold", &bag_map, &mut parents);
-    print!("Day 7 - A: {:?}", parents.len());
-}
+        let mut parents: HashSet<&str> = HashSet::new();
+        find_distinct_parents("shiny gold", &bag_map, &mut parents);
+        parents.len().into()
+    }
 
-fn part_two() {
-    let mut bag_map: BagMap = HashMap::new();
+    fn part_two(&self, rules: &Vec<Rule>) -> Answer {
+        let mut bag_map: BagMap = HashMap::new();
 
-    for rule in get_rules() {
-        let parent_children: Vec<&str> = rule.split(" contain ").collect();
-        let parent = parse_bag_type(parent_children[0], 0);
-        let children: Vec<Bag> = parent_children[1].split(",").map(parse_bag).collect();
-        bag_map.insert(parent, children);
+        for rule in rules {
+            bag_map.insert(rule.parent.to_owned(), rule.children.clone());
+        }
+        bag_map.insert("no other".to_string(), Vec::new());
+        let count = total_bag_count("shiny gold", &bag_map).map(|count| count - 1);
+        count.into()
     }
-    bag_map.insert("no other".to_string(), Vec::new());
-    let count = total_bag_count("shiny gold", &bag_map) - 1;
-    println!(", B: {:?}", count);
 }
 
-fn total_bag_count(bag_type: &str, map: &BagMap) -> i32 {
-    let mut sum = 1;
-    let bags = map.get(bag_type).unwrap();
-    for bag in bags {
-        sum += total_bag_count(&bag.bag_type, map) * bag.count;
+/// The bag itself plus everything inside it, `None` if a bag in there has no
+/// rule or the count doesn't fit in an `i32`.
+fn total_bag_count(bag_type: &str, map: &BagMap) -> Option<i32> {
+    let mut sum: i32 = 1;
+    for bag in map.get(bag_type)? {
+        let inside = total_bag_count(&bag.bag_type, map)?.checked_mul(bag.count)?;
+        sum = sum.checked_add(inside)?;
     }
-    sum
+    Some(sum)
+}
+
+fn parse_rule(ctx: &Context, line: &str) -> Result<Rule, ParseError> {
+    let mut parent_children = line.splitn(2, " contain ");
+    let parent = ctx.field(parent_children.next(), line, "bag")?;
+    let children = ctx.field(parent_children.next(), parent, "` contain ` and contents")?;
+    Ok(Rule {
+        parent: parse_bag_type(ctx, parent, 0)?,
+        children: children
+            .split(',')
+            .map(|child| parse_bag(ctx, child))
+            .collect::<Result<_, _>>()?,
+    })
 }
 
-fn parse_bag(string: &str) -> Bag {
-    let words: Vec<&str> = string.trim().split(" ").collect();
-    match words[0].parse() {
-        Ok(count) => Bag {
-            count,
-            bag_type: words[1..words.len() - 1].join(" "),
-        },
-        Err(_) => Bag {
+fn parse_bag(ctx: &Context, string: &str) -> Result<Bag, ParseError> {
+    let string = string.trim();
+    if string.starts_with("no other bags") {
+        return Ok(Bag {
             count: 0,
             bag_type: "no other".to_string(),
-        },
+        });
     }
+
+    let count = string.split(' ').next().unwrap_or(string);
+    Ok(Bag {
+        count: ctx.number(count)?,
+        bag_type: parse_bag_type(ctx, string, 1)?,
+    })
 }
 
 fn find_distinct_parents<'a>(bag: &str, bag_map: &'a BagMap, set: &mut HashSet<&'a str>) {
-    match bag_map.get(bag) {
-        Some(parents) => {
-            for parent in parents {
-                set.insert(&parent.bag_type);
+    if let Some(parents) = bag_map.get(bag) {
+        for parent in parents {
+            if set.insert(&parent.bag_type) {
                 find_distinct_parents(&parent.bag_type, bag_map, set);
             }
         }
-        None => {}
     }
 }
 
-fn parse_bag_type(subrule: &str, start: usize) -> String {
-    let words: Vec<&str> = subrule.trim().split(" ").collect();
-    words[start..words.len() - 1].join(" ")
-}
-
-fn get_rules() -> Vec<String> {
-    fs::read_to_string("data/day7.txt")
-        .unwrap()
-        .lines()
-        .map(String::from)
-        .collect()
+fn parse_bag_type(ctx: &Context, subrule: &str, start: usize) -> Result<String, ParseError> {
+    let subrule = subrule.trim();
+    let words: Vec<&str> = subrule.split(' ').collect();
+    if words.len() < start + 2 {
+        return Err(ctx.error(subrule, "expected a bag like `shiny gold bags`"));
+    }
+    Ok(words[start..words.len() - 1].join(" "))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{total_bag_count, Bag, BagMap};
+    use super::{total_bag_count, Bag, BagMap, Day7};
+    use crate::solution::{Answer, Solution};
     use std::collections::HashMap;
 
+    #[test]
+    fn parse_reads_rules_and_reports_bad_counts() {
+        let rules = Day7
+            .parse(
+                "light red bags contain 1 bright white bag, 2 muted yellow bags.\n\
+                 bright white bags contain 1 shiny gold bag.\n\
+                 muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\n\
+                 shiny gold bags contain no other bags.",
+            )
+            .unwrap();
+        assert_eq!(rules[0].parent, "light red");
+        assert_eq!(rules[0].children[1].bag_type, "muted yellow");
+        assert_eq!(Day7.part_one(&rules), Answer::Number(3));
+
+        let err = Day7
+            .parse("light red bags contain one bright white bag.")
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 24, "one"));
+        let err = Day7.parse("light red bags").unwrap_err();
+        assert_eq!(err.column, 15);
+    }
+
     #[test]
     fn total_bag_count_works() {
         let mut map: BagMap = HashMap::new();
@@ -139,6 +184,23 @@ mod tests {
             }],
         );
         map.insert("none".to_string(), Vec::new());
-        assert_eq!(total_bag_count("A", &map), 7);
+        assert_eq!(total_bag_count("A", &map), Some(7));
+    }
+
+    #[test]
+    fn rules_without_a_shiny_gold_bag_have_no_answer() {
+        let rules = Day7
+            .parse(
+                "light red bags contain 1 bright white bag.
+bright white bags contain no other bags.",
+            )
+            .unwrap();
+        assert_eq!(Day7.part_one(&rules), Answer::Number(0));
+        assert_eq!(Day7.part_two(&rules), Answer::None);
+
+        let rules = Day7
+            .parse("shiny gold bags contain 2 dark red bags.")
+            .unwrap();
+        assert_eq!(Day7.part_two(&rules), Answer::None);
     }
 }
@@ -1,13 +1,19 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 
-struct Code {
-    op: String,
-    int: i32,
-    sign: char,
+/// One boot code instruction, e.g. `jmp -4`.
+#[derive(Debug, Clone)]
+pub struct Code {
+    pub op: String,
+    pub int: i32,
+    pub sign: char,
     accessed: i32,
 }
 
-struct Program {
+/// The handheld's boot code VM: an accumulator and an instruction pointer.
+pub struct Program {
     acc: i32,
     ptr: i32,
     codes: Vec<Code>,
@@ -22,9 +28,14 @@ impl Program {
         }
     }
 
-    fn exec(&mut self) -> i32 {
+    /// Runs from the first instruction until the program falls off the end (0)
+    /// or is about to run an instruction a second time (1).
+    pub fn exec(&mut self) -> i32 {
         self.acc = 0;
         self.ptr = 0;
+        for code in &mut self.codes {
+            code.accessed = 0;
+        }
         loop {
             if self.ptr as usize >= self.codes.len() {
                 return 0;
@@ -54,61 +65,101 @@ impl Program {
             }
         }
     }
-}
 
-pub fn execute() {
-    part_one();
-    part_two();
+    pub fn acc(&self) -> i32 {
+        self.acc
+    }
 }
 
-fn part_one() {
-    let mut prg = Program::new(get_codes());
-    prg.exec();
-    print!("Day 8 - A: {:?}", prg.acc);
-}
+pub struct Day8;
 
-fn part_two() {
-    let codes = get_codes();
-    let mut prg = Program::new(codes);
-    let mut last_change = 0;
-
-    while prg.exec() == 1 {
-        let mut codes = get_codes();
-        for i in last_change..codes.len() {
-            match codes[i].op.as_str() {
-                "jmp" => {
-                    codes[i].op = "nop".to_string();
-                    last_change = i + 1;
-                    break;
-                }
-                "nop" => {
-                    codes[i].op = "jmp".to_string();
-                    last_change = i + 1;
-                    break;
+impl Solution for Day8 {
+    type Input = Vec<Code>;
+
+    const DAY: u8 = 8;
+
+    fn parse(&self, input: &str) -> Result<Vec<Code>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input.lines().map(|line| parse_code(&ctx, line)).collect()
+    }
+
+    fn part_one(&self, codes: &Vec<Code>) -> Answer {
+        let mut prg = Program::new(codes.clone());
+        prg.exec();
+        prg.acc.into()
+    }
+
+    fn part_two(&self, codes: &Vec<Code>) -> Answer {
+        let mut prg = Program::new(codes.clone());
+        let mut last_change = 0;
+
+        while prg.exec() == 1 {
+            let mut codes = codes.clone();
+            for (i, code) in codes.iter_mut().enumerate().skip(last_change) {
+                match code.op.as_str() {
+                    "jmp" => {
+                        code.op = "nop".to_string();
+                        last_change = i + 1;
+                        break;
+                    }
+                    "nop" => {
+                        code.op = "jmp".to_string();
+                        last_change = i + 1;
+                        break;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
+            prg.codes = codes;
         }
-        prg.codes = codes;
-    }
 
-    println!(", B: {:?}", prg.acc);
+        prg.acc.into()
+    }
 }
 
-fn get_codes() -> Vec<Code> {
-    fs::read_to_string("data/day8.txt")
-        .unwrap()
-        .lines()
-        .map(parse_code)
-        .collect()
-}
+fn parse_code(ctx: &Context, line: &str) -> Result<Code, ParseError> {
+    let mut ops = line.split(' ');
+    let op = ctx.field(ops.next(), line, "operation")?;
+    let arg = ctx.field(ops.next(), op, "argument")?;
+    if !["acc", "jmp", "nop"].contains(&op) {
+        return Err(ctx.error(op, "expected `acc`, `jmp` or `nop`"));
+    }
 
-fn parse_code(line: &str) -> Code {
-    let ops: Vec<&str> = line.split(" ").collect();
-    Code {
-        op: ops[0].to_string(),
-        int: ops[1][1..].parse().unwrap(),
-        sign: ops[1].chars().nth(0).unwrap(),
+    let sign = match arg.chars().next() {
+        Some(sign @ '+') | Some(sign @ '-') => sign,
+        _ => return Err(ctx.error(arg, "expected a signed argument like `+3`")),
+    };
+    Ok(Code {
+        op: op.to_string(),
+        int: ctx.number(&arg[1..])?,
+        sign,
         accessed: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day8;
+    use crate::solution::{Answer, Solution};
+
+    #[test]
+    fn parse_reports_bad_instructions() {
+        let err = Day8.parse("nop +0\nacx +1").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "acx"));
+
+        let err = Day8.parse("jmp 4").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (5, "4"));
+
+        let err = Day8.parse("acc +x").err().unwrap();
+        assert_eq!((err.column, err.text.as_str()), (6, "x"));
+    }
+
+    #[test]
+    fn part_two_repairs_the_loop() {
+        let codes = Day8
+            .parse("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6")
+            .unwrap();
+        assert_eq!(Day8.part_one(&codes), Answer::Number(5));
+        assert_eq!(Day8.part_two(&codes), Answer::Number(8));
     }
 }
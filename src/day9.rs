@@ -1,22 +1,53 @@
-use std::fs;
+use crate::{
+    error::{Context, ParseError},
+    solution::{Answer, Solution},
+};
 
-pub fn execute() {
-    part_one();
-    part_two();
+pub struct Day9 {
+    /// Number of preceding numbers each number must be a sum of two of.
+    pub preamble: usize,
 }
 
-fn part_one() {
-    const WIN_SIZE: usize = 25;
-    let nums = get_numbers();
+impl Default for Day9 {
+    fn default() -> Self {
+        Self { preamble: 25 }
+    }
+}
+
+impl Solution for Day9 {
+    type Input = Vec<usize>;
 
-    for (i, window) in nums.windows(WIN_SIZE).enumerate() {
+    const DAY: u8 = 9;
+
+    fn parse(&self, input: &str) -> Result<Vec<usize>, ParseError> {
+        let ctx = Context::new(Self::DAY, input);
+        input.lines().map(|num| ctx.number(num)).collect()
+    }
+
+    fn part_one(&self, nums: &Vec<usize>) -> Answer {
+        first_invalid_number(nums, self.preamble).into()
+    }
+
+    fn part_two(&self, nums: &Vec<usize>) -> Answer {
+        first_invalid_number(nums, self.preamble)
+            .and_then(|target| encryption_weakness(nums, target))
+            .into()
+    }
+}
+
+pub fn first_invalid_number(nums: &[usize], preamble: usize) -> Option<usize> {
+    // With no preamble there's nothing a number could be the sum of.
+    if preamble == 0 {
+        return None;
+    }
+    for (i, window) in nums.windows(preamble).enumerate() {
         let mut pair_found = false;
-        let index = i + WIN_SIZE;
+        let index = i + preamble;
         if index >= nums.len() {
             break;
         }
 
-        let next_num = nums[i + WIN_SIZE];
+        let next_num = nums[index];
         'sum: for (idx, num) in window.iter().enumerate() {
             for other in &window[idx + 1..] {
                 let sum = num + other;
@@ -27,15 +58,13 @@ fn part_one() {
             }
         }
         if !pair_found {
-            print!("Day 9 - A: {:?}", next_num);
-            return;
+            return Some(next_num);
         }
     }
+    None
 }
 
-fn part_two() {
-    const TARGET: usize = 57195069;
-    let nums = get_numbers();
+pub fn encryption_weakness(nums: &[usize], target: usize) -> Option<usize> {
     for (i, &num) in nums.iter().enumerate() {
         let mut sum = num;
         let mut highest_num = num;
@@ -49,20 +78,34 @@ fn part_two() {
             }
 
             sum += other;
-            if sum == TARGET {
-                println!(" - B: {:?}", smallest_num + highest_num);
-                return;
-            } else if sum > TARGET {
+            if sum == target {
+                return Some(smallest_num + highest_num);
+            } else if sum > target {
                 break;
             }
         }
     }
+    None
 }
 
-fn get_numbers() -> Vec<usize> {
-    fs::read_to_string("data/day9.txt")
-        .unwrap()
-        .lines()
-        .map(|num| num.parse().unwrap())
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::{encryption_weakness, first_invalid_number};
+
+    const NUMBERS: [usize; 20] = [
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+
+    #[test]
+    fn first_invalid_number_works() {
+        assert_eq!(first_invalid_number(&NUMBERS, 5), Some(127));
+        assert_eq!(first_invalid_number(&NUMBERS[..14], 5), None);
+        assert_eq!(first_invalid_number(&NUMBERS, 0), None);
+    }
+
+    #[test]
+    fn encryption_weakness_works() {
+        assert_eq!(encryption_weakness(&NUMBERS, 127), Some(62));
+        assert_eq!(encryption_weakness(&NUMBERS, 1), None);
+    }
 }
@@ -0,0 +1,162 @@
+use std::{error, fmt, str::FromStr};
+
+/// A malformed piece of puzzle input, located by 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, ", found `{}`", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// The full input of one day, used to turn slices of it into located errors.
+///
+/// Parsers should keep working on `&str` slices borrowed from `input` (`lines`,
+/// `split`, `trim`, ...) so that any slice handed to `error` can be located.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    day: u8,
+    input: &'a str,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(day: u8, input: &'a str) -> Self {
+        Self { day, input }
+    }
+
+    /// Builds an error pointing at `text`, which should be a slice of the input.
+    pub fn error(&self, text: &str, message: impl Into<String>) -> ParseError {
+        let (line, column) = self.locate(text);
+        ParseError {
+            day: self.day,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Builds an error pointing just past the end of `text`, for input that stops too early.
+    pub fn missing(&self, text: &str, message: impl Into<String>) -> ParseError {
+        self.error(&text[text.len()..], message)
+    }
+
+    /// Parses `text` as a number, reporting it as the offending text on failure.
+    pub fn number<T: FromStr>(&self, text: &str) -> Result<T, ParseError> {
+        text.parse()
+            .map_err(|_| self.error(text, "expected a number"))
+    }
+
+    /// Unwraps the next piece of a split, or reports what was missing after `text`.
+    pub fn field<'b>(
+        &self,
+        field: Option<&'b str>,
+        text: &str,
+        name: &str,
+    ) -> Result<&'b str, ParseError> {
+        field.ok_or_else(|| self.missing(text, format!("missing {}", name)))
+    }
+
+    /// Checks that every character of `text` is one of `allowed`.
+    pub fn expect_chars(&self, text: &str, allowed: &str) -> Result<(), ParseError> {
+        match text.char_indices().find(|&(_, ch)| !allowed.contains(ch)) {
+            Some((i, ch)) => Err(self.error(
+                &text[i..i + ch.len_utf8()],
+                format!("unexpected character, expected one of `{}`", allowed),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn locate(&self, text: &str) -> (usize, usize) {
+        let start = self.input.as_ptr() as usize;
+        let offset = (text.as_ptr() as usize).wrapping_sub(start);
+        if offset > self.input.len() || !self.input.is_char_boundary(offset) {
+            return (0, 0);
+        }
+
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+
+    #[test]
+    fn error_locates_slices_of_the_input() {
+        let input = "1-3 a: abcde\n1-x b: cdefg\n";
+        let ctx = Context::new(2, input);
+        let line = input.lines().nth(1).unwrap();
+        let err = ctx.error(&line[2..3], "expected a number");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.text, "x");
+        assert_eq!(
+            err.to_string(),
+            "day 2, line 2, column 3: expected a number, found `x`"
+        );
+    }
+
+    #[test]
+    fn missing_points_past_the_text() {
+        let input = "abc\nde";
+        let ctx = Context::new(1, input);
+        let err = ctx.missing(&input[4..], "missing colon");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.to_string(), "day 1, line 2, column 3: missing colon");
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let input = "pässword: x";
+        let ctx = Context::new(2, input);
+        assert_eq!(ctx.error(&input[11..], "bad").column, 11);
+    }
+
+    #[test]
+    fn number_reports_the_offending_text() {
+        let input = "12\nl3";
+        let ctx = Context::new(1, input);
+        assert_eq!(ctx.number::<i32>(&input[..2]), Ok(12));
+        let err = ctx.number::<i32>(&input[3..]).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "l3"));
+    }
+
+    #[test]
+    fn expect_chars_reports_the_first_unexpected_character() {
+        let input = "..#\n.#x#";
+        let ctx = Context::new(3, input);
+        assert!(ctx.expect_chars(&input[..3], ".#").is_ok());
+        let err = ctx.expect_chars(&input[4..], ".#").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+    }
+
+    #[test]
+    fn text_outside_the_input_has_no_location() {
+        let ctx = Context::new(1, "abc");
+        let elsewhere = "elsewhere".to_string();
+        let err = ctx.error(&elsewhere, "bad");
+        assert_eq!((err.line, err.column), (0, 0));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    env,
+    ffi::OsString,
+    fmt, fs,
+    io::{self, BufRead, BufReader, Read},
+    path::PathBuf,
+};
+
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+const DEFAULT_DATA_DIR: &str = "data";
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `dayN.txt` inside `$AOC_DATA_DIR`, or `data/` when the variable is unset.
+    DataDir,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets an `--input` argument, where `-` means stdin.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    location: String,
+    source: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.source.kind() {
+            io::ErrorKind::NotFound => write!(
+                f,
+                "input file {} not found (pass --input <file> or set {})",
+                self.location, DATA_DIR_VAR
+            ),
+            _ => write!(f, "could not read {}: {}", self.location, self.source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+pub fn read(day: u8, source: &Source) -> Result<String, InputError> {
+    match source {
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|source| InputError {
+                    location: "stdin".to_string(),
+                    source,
+                })?;
+            Ok(input)
+        }
+        Source::File(path) => read_file(path.clone()),
+        Source::DataDir => read_file(default_path(day)),
+    }
+}
+
+/// Opens a day's input for reading line by line instead of all at once.
+pub fn open(day: u8, source: &Source) -> Result<Box<dyn BufRead>, InputError> {
+    let path = match source {
+        Source::Stdin => return Ok(Box::new(BufReader::new(io::stdin()))),
+        Source::File(path) => path.clone(),
+        Source::DataDir => default_path(day),
+    };
+    let file = fs::File::open(&path).map_err(|source| InputError {
+        location: path.display().to_string(),
+        source,
+    })?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+fn read_file(path: PathBuf) -> Result<String, InputError> {
+    fs::read_to_string(&path).map_err(|source| InputError {
+        location: path.display().to_string(),
+        source,
+    })
+}
+
+/// Path of a day's input in `$AOC_DATA_DIR`, or `data/` when the variable is unset.
+pub fn default_path(day: u8) -> PathBuf {
+    data_path(env::var_os(DATA_DIR_VAR), day)
+}
+
+fn data_path(data_dir: Option<OsString>, day: u8) -> PathBuf {
+    let data_dir = data_dir
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR));
+    data_dir.join(format!("day{}.txt", day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{data_path, open, read, Source};
+    use std::{ffi::OsString, path::PathBuf};
+
+    #[test]
+    fn data_path_falls_back_to_data_dir() {
+        assert_eq!(data_path(None, 7), PathBuf::from("data/day7.txt"));
+        assert_eq!(
+            data_path(Some(OsString::new()), 7),
+            PathBuf::from("data/day7.txt")
+        );
+        assert_eq!(
+            data_path(Some(OsString::from("/tmp/aoc")), 12),
+            PathBuf::from("/tmp/aoc/day12.txt")
+        );
+    }
+
+    #[test]
+    fn from_arg_treats_dash_as_stdin() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("input.txt"),
+            Source::File(PathBuf::from("input.txt"))
+        );
+    }
+
+    #[test]
+    fn missing_file_error_names_the_file() {
+        let source = Source::File(PathBuf::from("no/such/day99.txt"));
+        let err = read(99, &source).unwrap_err();
+        assert!(err.to_string().contains("no/such/day99.txt"));
+        assert!(open(99, &source).is_err());
+    }
+}
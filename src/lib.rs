@@ -0,0 +1,37 @@
+//! Advent of Code 2020 solutions.
+//!
+//! Every day lives in its own module and implements [`Solution`]; the pieces
+//! worth reusing on their own (the day 8 VM, the day 18 Pratt parser, the day 19
+//! rule-to-regex compiler, ...) are public too. [`registry`] lists every day as a
+//! [`Puzzle`] for front-ends such as the `aoc2020` binary.
+
+pub mod bench;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod input;
+pub mod records;
+pub mod registry;
+pub mod seating;
+pub mod solution;
+pub mod verify;
+
+pub use error::ParseError;
+pub use solution::{Answer, Part, Puzzle, Solution};
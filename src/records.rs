@@ -0,0 +1,182 @@
+//! Inputs made of blocks of lines separated by blank lines, like day 4's
+//! passports, day 6's answer groups and day 16's notes, and the `key:value`
+//! records some of those blocks hold.
+//!
+//! Everything here hands back slices of the original input, so a [`Context`]
+//! can still point errors at the right line. `\r\n` line endings, trailing
+//! whitespace and blank lines made only of whitespace are all tolerated.
+
+use crate::error::{Context, ParseError};
+use std::iter::{self, FromIterator};
+
+/// Splits `input` into its blank-line separated blocks, each running from the
+/// start of its first line to the end of its last line's content.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    iter::from_fn(move || {
+        while !rest.is_empty() {
+            let (line, next) = split_line(rest);
+            if !line.trim().is_empty() {
+                break;
+            }
+            rest = next;
+        }
+        if rest.is_empty() {
+            return None;
+        }
+
+        let block = rest;
+        let mut end = 0;
+        while !rest.is_empty() {
+            let (line, next) = split_line(rest);
+            if line.trim().is_empty() {
+                break;
+            }
+            end = block.len() - rest.len() + line.trim_end().len();
+            rest = next;
+        }
+        Some(&block[..end])
+    })
+}
+
+/// The lines of a block without their trailing whitespace.
+pub fn lines(block: &str) -> impl Iterator<Item = &str> {
+    block.lines().map(str::trim_end)
+}
+
+/// Splits off the first line, returning it with its line ending and the rest.
+fn split_line(text: &str) -> (&str, &str) {
+    let end = text.find('\n').map_or(text.len(), |i| i + 1);
+    text.split_at(end)
+}
+
+/// Whitespace separated `key:value` fields, in input order. Keys may repeat,
+/// and values may contain `:` themselves since only the first one splits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    fields: Vec<(String, String)>,
+}
+
+impl Record {
+    /// The first value given for `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields()
+            .find(|&(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Every value given for `key`, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.fields()
+            .filter(move |&(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The keys in the order they first appear.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        for (name, _) in self.fields() {
+            if !keys.contains(&name) {
+                keys.push(name);
+            }
+        }
+        keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Record {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(fields: I) -> Self {
+        Record {
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Parses one block of `key:value` fields.
+pub fn parse_record(ctx: &Context, block: &str) -> Result<Record, ParseError> {
+    block
+        .split_whitespace()
+        .map(|field| {
+            let mut parts = field.splitn(2, ':');
+            let name = parts.next().unwrap_or(field);
+            let value = ctx.field(parts.next(), name, "`:` between field name and value")?;
+            if name.is_empty() {
+                return Err(ctx.error(field, "expected a field name before `:`"));
+            }
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Parses every blank-line separated block of `input` as a record.
+pub fn parse_records(ctx: &Context, input: &str) -> Result<Vec<Record>, ParseError> {
+    blocks(input)
+        .map(|block| parse_record(ctx, block))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocks, lines, parse_record, parse_records, Record};
+    use crate::error::Context;
+
+    #[test]
+    fn blocks_tolerate_crlf_and_whitespace_only_lines() {
+        let input = "\r\na b \r\nc\r\n \t\r\n\r\nd\r\n\n\n";
+        let found: Vec<&str> = blocks(input).collect();
+        assert_eq!(found, vec!["a b \r\nc", "d"]);
+        assert_eq!(lines(found[0]).collect::<Vec<_>>(), vec!["a b", "c"]);
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("x").collect::<Vec<_>>(), vec!["x"]);
+    }
+
+    #[test]
+    fn records_keep_repeated_keys_and_colons_in_values() {
+        let input = "ecl:gry  url:http://x:80\r\necl:blu \r\n\r\nhgt:60in";
+        let records = parse_records(&Context::new(4, input), input).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("ecl"), Some("gry"));
+        assert_eq!(
+            records[0].get_all("ecl").collect::<Vec<_>>(),
+            vec!["gry", "blu"]
+        );
+        assert_eq!(records[0].get("url"), Some("http://x:80"));
+        assert_eq!(records[0].keys(), vec!["ecl", "url"]);
+        assert_eq!(
+            records[1],
+            vec![("hgt", "60in")].into_iter().collect::<Record>()
+        );
+        assert!(!records[1].contains_key("ecl"));
+    }
+
+    #[test]
+    fn parse_record_locates_bad_fields() {
+        let input = "a:1\nb :2";
+        let ctx = Context::new(4, input);
+        let err = parse_record(&ctx, input).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = parse_record(&ctx, &input[5..]).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, ":2"));
+    }
+}
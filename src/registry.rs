@@ -0,0 +1,31 @@
+// Glob import so `aoc2020 new` only has to add a line to `all` when it registers a day.
+use crate::*;
+
+/// Every implemented day, in order.
+pub fn all() -> Vec<Box<dyn Puzzle>> {
+    vec![
+        Box::new(day1::Day1::default()),
+        Box::new(day2::Day2),
+        Box::new(day3::Day3),
+        Box::new(day4::Day4::default()),
+        Box::new(day5::Day5::default()),
+        Box::new(day6::Day6),
+        Box::new(day7::Day7),
+        Box::new(day8::Day8),
+        Box::new(day9::Day9::default()),
+        Box::new(day10::Day10),
+        Box::new(day11::Day11),
+        Box::new(day12::Day12),
+        Box::new(day13::Day13),
+        Box::new(day14::Day14),
+        Box::new(day15::Day15),
+        Box::new(day16::Day16),
+        Box::new(day17::Day17),
+        Box::new(day18::Day18),
+        Box::new(day19::Day19),
+    ]
+}
+
+pub fn find(day: u8) -> Option<Box<dyn Puzzle>> {
+    all().into_iter().find(|puzzle| puzzle.day() == day)
+}
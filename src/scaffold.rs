@@ -0,0 +1,191 @@
+use aoc2020::input;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs.in");
+const PLACEHOLDER: &str = "{{day}}";
+
+/// Generates `src/dayN.rs` from `templates/day.rs.in`, registers it in `lib.rs`
+/// and the registry, and creates empty input and example fixture files. `root`
+/// is the directory holding `Cargo.toml`. Returns the files that were written.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {} is outside the advent calendar (1-25)", day));
+    }
+    if !root.join("Cargo.toml").is_file() {
+        return Err(format!(
+            "{} is not the project root (no Cargo.toml)",
+            root.display()
+        ));
+    }
+
+    let source = root.join("src").join(format!("day{}.rs", day));
+    if source.exists() {
+        return Err(format!("day {} already exists ({})", day, source.display()));
+    }
+
+    let lib = root.join("src").join("lib.rs");
+    let registry = root.join("src").join("registry.rs");
+    let lib_contents = register_module(&read(&lib)?, day)?;
+    let registry_contents = register_puzzle(&read(&registry)?, day)?;
+
+    write(&source, &render(day))?;
+    write(&lib, &lib_contents)?;
+    write(&registry, &registry_contents)?;
+    let mut written = vec![source, lib, registry];
+
+    let data = root.join(input::default_path(day));
+    let fixture = root.join("fixtures").join(format!("day{}.txt", day));
+    for path in [data, fixture].iter() {
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)
+                    .map_err(|err| format!("could not create {}: {}", dir.display(), err))?;
+            }
+            write(path, "")?;
+            written.push(path.clone());
+        }
+    }
+    Ok(written)
+}
+
+fn render(day: u8) -> String {
+    TEMPLATE.replace(PLACEHOLDER, &day.to_string())
+}
+
+/// Adds `pub mod dayN;` to the sorted block of `pub mod` lines in `lib.rs`.
+fn register_module(lib: &str, day: u8) -> Result<String, String> {
+    let module = format!("pub mod day{};", day);
+    let mut lines: Vec<&str> = lib.lines().collect();
+    if lines.contains(&module.as_str()) {
+        return Err(format!("day{} is already declared in lib.rs", day));
+    }
+
+    let first = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod "))
+        .ok_or("lib.rs has no `pub mod` declarations")?;
+    let len = lines[first..]
+        .iter()
+        .take_while(|line| line.starts_with("pub mod "))
+        .count();
+    let at = first
+        + lines[first..first + len]
+            .iter()
+            .take_while(|line| line.trim_end_matches(';') < module.trim_end_matches(';'))
+            .count();
+    lines.insert(at, &module);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `Box::new(dayN::DayN)` to the registry's list, keeping days in order.
+fn register_puzzle(registry: &str, day: u8) -> Result<String, String> {
+    let entry = format!("        Box::new(day{0}::Day{0}),", day);
+    let mut lines: Vec<&str> = registry.lines().collect();
+
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == "vec![")
+        .ok_or("registry.rs has no `vec![` of puzzles")?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line.trim() == "]")
+            .ok_or("registry.rs has an unterminated `vec![`")?;
+    let at = (start + 1..end)
+        .find(|&i| registered_day(lines[i]).is_some_and(|other| other > day))
+        .unwrap_or(end);
+    lines.insert(at, &entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn registered_day(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix("Box::new(day")?;
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("could not read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("could not write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new_day, register_module, register_puzzle, render};
+    use std::{env, fs};
+
+    #[test]
+    fn render_fills_in_the_day() {
+        let source = render(20);
+        assert!(source.contains("pub struct Day20;"));
+        assert!(source.contains("const DAY: u8 = 20;"));
+        assert!(!source.contains("{{"));
+    }
+
+    #[test]
+    fn register_module_keeps_declarations_sorted() {
+        let lib = "//! docs\n\n\
+                   pub mod day1;\n\
+                   pub mod day19;\n\
+                   pub mod day2;\n\
+                   pub mod error;\n\n\
+                   pub use x;\n";
+        assert_eq!(
+            register_module(lib, 20).unwrap(),
+            "//! docs\n\n\
+             pub mod day1;\n\
+             pub mod day19;\n\
+             pub mod day2;\n\
+             pub mod day20;\n\
+             pub mod error;\n\n\
+             pub use x;\n"
+        );
+        assert!(register_module(lib, 19).is_err());
+    }
+
+    #[test]
+    fn register_puzzle_keeps_days_in_order() {
+        let registry = "fn all() {\n    vec![\n\
+                        \x20       Box::new(day1::Day1),\n\
+                        \x20       Box::new(day9::Day9::default()),\n\
+                        \x20       Box::new(day22::Day22),\n    ]\n}\n";
+        let registered = register_puzzle(registry, 20).unwrap();
+        assert!(registered.contains(
+            "Day9::default()),\n        Box::new(day20::Day20),\n        Box::new(day22"
+        ));
+        let appended = register_puzzle(registry, 25).unwrap();
+        assert!(appended.contains("Box::new(day25::Day25),\n    ]"));
+    }
+
+    #[test]
+    fn new_day_refuses_existing_days() {
+        let root = env::temp_dir().join(format!("aoc2020-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "").unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day1;\n").unwrap();
+        fs::write(
+            root.join("src/registry.rs"),
+            "    vec![\n        Box::new(day1::Day1),\n    ]\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/day1.rs"), "").unwrap();
+
+        assert!(new_day(&root, 1).unwrap_err().contains("already exists"));
+        assert!(new_day(&root, 26).is_err());
+        let written = new_day(&root, 2).unwrap();
+        assert_eq!(written.len(), 5);
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("pub mod day2;"));
+        assert!(root.join("fixtures/day2.txt").is_file());
+        assert!(new_day(&root, 2).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
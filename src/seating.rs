@@ -0,0 +1,169 @@
+//! The seating chart for day 5: which seats on the plane the boarding passes
+//! fill, which are left empty, and which were handed out more than once.
+
+use crate::day5::{BoardingPass, Geometry};
+
+/// How many passes name each seat of a plane, indexed by seat id. The whole
+/// plane is allocated up front, so this is meant for realistic geometries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatingChart {
+    geometry: Geometry,
+    passes: Vec<u32>,
+}
+
+/// The empty seats, split by seat id into those before the first taken seat
+/// (rows missing from the front of the plane), those after the last (missing
+/// from the back) and the gaps in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vacancies {
+    pub front: Vec<BoardingPass>,
+    pub gaps: Vec<BoardingPass>,
+    pub back: Vec<BoardingPass>,
+}
+
+impl SeatingChart {
+    /// Seats every pass, which must all be for the given plane.
+    pub fn new(geometry: Geometry, passes: &[BoardingPass]) -> SeatingChart {
+        let seats = geometry.rows() as usize * geometry.columns() as usize;
+        let mut chart = SeatingChart {
+            geometry,
+            passes: vec![0; seats],
+        };
+        for pass in passes {
+            assert_eq!(
+                pass.geometry(),
+                geometry,
+                "every pass must be for the same plane"
+            );
+            chart.passes[pass.id() as usize] += 1;
+        }
+        chart
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    /// How many passes were issued for the seat.
+    pub fn passes_for(&self, seat: &BoardingPass) -> u32 {
+        self.passes[seat.id() as usize]
+    }
+
+    pub fn is_taken(&self, seat: &BoardingPass) -> bool {
+        self.passes_for(seat) > 0
+    }
+
+    /// Every seat named by more than one pass, with how many, in seat id order.
+    pub fn duplicates(&self) -> Vec<(BoardingPass, u32)> {
+        self.seats()
+            .filter_map(|seat| match self.passes_for(&seat) {
+                count if count > 1 => Some((seat, count)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn vacancies(&self) -> Vacancies {
+        let first = self.passes.iter().position(|&count| count > 0);
+        let first = first.unwrap_or(self.passes.len());
+        let last = self
+            .passes
+            .iter()
+            .rposition(|&count| count > 0)
+            .unwrap_or(0);
+        let mut vacancies = Vacancies {
+            front: Vec::new(),
+            gaps: Vec::new(),
+            back: Vec::new(),
+        };
+        for seat in self.seats().filter(|seat| !self.is_taken(seat)) {
+            let id = seat.id() as usize;
+            if id < first {
+                vacancies.front.push(seat);
+            } else if id > last {
+                vacancies.back.push(seat);
+            } else {
+                vacancies.gaps.push(seat);
+            }
+        }
+        vacancies
+    }
+
+    /// Draws the plane front to back, one row per line with an aisle down the
+    /// middle: `#` for a taken seat, `.` for an empty one and `!` for a seat
+    /// with more than one pass.
+    pub fn render(&self) -> String {
+        let columns = self.geometry.columns() as usize;
+        let label = (self.geometry.rows() - 1).to_string().len();
+        let mut rendered = String::new();
+        for (row, seats) in self.passes.chunks(columns).enumerate() {
+            rendered.push_str(&format!("{:>width$} ", row, width = label));
+            for (column, &count) in seats.iter().enumerate() {
+                if column == columns / 2 && columns > 1 {
+                    rendered.push(' ');
+                }
+                rendered.push(match count {
+                    0 => '.',
+                    1 => '#',
+                    _ => '!',
+                });
+            }
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    fn seats(&self) -> impl Iterator<Item = BoardingPass> + '_ {
+        (0..self.passes.len()).filter_map(move |id| BoardingPass::from_id(id as u32, self.geometry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeatingChart;
+    use crate::day5::{BoardingPass, Geometry};
+
+    fn chart(ids: &[u32]) -> SeatingChart {
+        let geometry = Geometry::new(4, 4).unwrap();
+        let passes: Vec<BoardingPass> = ids
+            .iter()
+            .map(|&id| BoardingPass::from_id(id, geometry).unwrap())
+            .collect();
+        SeatingChart::new(geometry, &passes)
+    }
+
+    fn ids(seats: &[BoardingPass]) -> Vec<u32> {
+        seats.iter().map(BoardingPass::id).collect()
+    }
+
+    #[test]
+    fn vacancies_split_the_front_and_back_from_interior_gaps() {
+        let vacancies = chart(&[5, 6, 8, 9, 11]).vacancies();
+        assert_eq!(ids(&vacancies.front), vec![0, 1, 2, 3, 4]);
+        assert_eq!(ids(&vacancies.gaps), vec![7, 10]);
+        assert_eq!(ids(&vacancies.back), vec![12, 13, 14, 15]);
+
+        let full = chart(&(0..16).collect::<Vec<u32>>()).vacancies();
+        assert!(full.front.is_empty() && full.gaps.is_empty() && full.back.is_empty());
+        assert_eq!(chart(&[]).vacancies().front.len(), 16);
+    }
+
+    #[test]
+    fn duplicates_count_every_extra_pass() {
+        let chart = chart(&[3, 6, 3, 9, 6, 3]);
+        let duplicates: Vec<(u32, u32)> = chart
+            .duplicates()
+            .iter()
+            .map(|(seat, count)| (seat.id(), *count))
+            .collect();
+        assert_eq!(duplicates, vec![(3, 3), (6, 2)]);
+    }
+
+    #[test]
+    fn render_draws_each_row_with_an_aisle() {
+        assert_eq!(
+            chart(&[0, 5, 6, 6, 15]).render(),
+            "0 #. ..\n1 .# !.\n2 .. ..\n3 .. .#\n"
+        );
+    }
+}
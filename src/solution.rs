@@ -0,0 +1,205 @@
+use crate::error::ParseError;
+use std::{
+    fmt,
+    hint::black_box,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "a" | "A" | "1" => Ok(Part::One),
+            "b" | "B" | "2" => Ok(Part::Two),
+            _ => Err(format!("unknown part `{}`, expected `a` or `b`", value)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("A"),
+            Part::Two => f.pad("B"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    None,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(num) => write!(f, "{}", num),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::None => write!(f, "no answer"),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(num: $ty) -> Self {
+                    Answer::Number(num as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(i32, i64, u32, u64, usize);
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+/// A day's puzzle: the input is parsed once and shared by both parts.
+pub trait Solution {
+    type Input;
+
+    const DAY: u8;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, ParseError>;
+    fn part_one(&self, input: &Self::Input) -> Answer;
+    fn part_two(&self, input: &Self::Input) -> Answer;
+}
+
+/// Object-safe view of a `Solution`, so days with different input types can
+/// live side by side in the registry.
+pub trait Puzzle {
+    fn day(&self) -> u8;
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Vec<Answer>, ParseError>;
+
+    /// Runs parse, part one and part two once, returning how long each took.
+    fn time(&self, input: &str) -> Result<Timing, ParseError>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+impl<S: Solution> Puzzle for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn solve(&self, input: &str, parts: &[Part]) -> Result<Vec<Answer>, ParseError> {
+        let input = self.parse(input)?;
+        Ok(parts
+            .iter()
+            .map(|part| match part {
+                Part::One => self.part_one(&input),
+                Part::Two => self.part_two(&input),
+            })
+            .collect())
+    }
+
+    fn time(&self, input: &str) -> Result<Timing, ParseError> {
+        let start = Instant::now();
+        let input = black_box(self.parse(input)?);
+        let parse = start.elapsed();
+
+        let start = Instant::now();
+        black_box(self.part_one(&input));
+        let part_one = start.elapsed();
+
+        let start = Instant::now();
+        black_box(self.part_two(&input));
+        let part_two = start.elapsed();
+
+        Ok(Timing {
+            parse,
+            part_one,
+            part_two,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, Part, Puzzle, Solution};
+    use crate::error::{Context, ParseError};
+
+    struct Lines;
+
+    impl Solution for Lines {
+        type Input = Vec<String>;
+
+        const DAY: u8 = 0;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, ParseError> {
+            if let Some(at) = input.find('!') {
+                let ctx = Context::new(Self::DAY, input);
+                return Err(ctx.error(&input[at..at + 1], "unexpected character"));
+            }
+            Ok(input.lines().map(String::from).collect())
+        }
+
+        fn part_one(&self, lines: &Self::Input) -> Answer {
+            lines.len().into()
+        }
+
+        fn part_two(&self, lines: &Self::Input) -> Answer {
+            lines.first().map(String::as_str).into()
+        }
+    }
+
+    #[test]
+    fn solve_returns_answers_in_part_order() {
+        let answers = Lines.solve("x\ny", &[Part::Two, Part::One]);
+        assert_eq!(answers, Ok(vec![Answer::from("x"), Answer::Number(2)]));
+        assert_eq!(Lines.solve("", &[Part::Two]), Ok(vec![Answer::None]));
+    }
+
+    #[test]
+    fn solve_stops_at_parse_errors() {
+        assert!(Lines.solve("x\n!", &[Part::One]).is_err());
+        assert!(Lines.time("!").is_err());
+    }
+
+    #[test]
+    fn part_from_str_works() {
+        assert_eq!("a".parse(), Ok(Part::One));
+        assert_eq!("B".parse(), Ok(Part::Two));
+        assert!("c".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn answers_display_their_value() {
+        assert_eq!(Answer::from(42_u64).to_string(), "42");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_eq!(Answer::from(None::<i32>).to_string(), "no answer");
+    }
+}
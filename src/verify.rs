@@ -0,0 +1,186 @@
+use crate::{
+    input::{self, Source},
+    solution::{Answer, Part, Puzzle},
+};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, Deserialize)]
+struct AnswersFile {
+    #[serde(default)]
+    answers: Vec<RawExpected>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawExpected {
+    day: u8,
+    part: String,
+    expected: RawValue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Number(i64),
+    Text(String),
+}
+
+/// A known-good answer for one part of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: Part,
+    pub value: String,
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub expected: Expected,
+    pub actual: Result<Answer, String>,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        match &self.actual {
+            Ok(answer) => answer.to_string() == self.expected.value,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Reads expected answers from a file of `[[answers]]` tables:
+///
+/// ```toml
+/// [[answers]]
+/// day = 14
+/// part = "a"
+/// expected = 165
+/// ```
+pub fn load(path: &Path) -> Result<Vec<Expected>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+    parse(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+fn parse(contents: &str) -> Result<Vec<Expected>, String> {
+    let file: AnswersFile = toml::from_str(contents).map_err(|err| err.to_string())?;
+    file.answers
+        .into_iter()
+        .map(|raw| {
+            Ok(Expected {
+                day: raw.day,
+                part: raw.part.parse()?,
+                value: match raw.expected {
+                    RawValue::Number(num) => num.to_string(),
+                    RawValue::Text(text) => text,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Runs every expected answer's solution from `puzzles` (usually `registry::all()`)
+/// against its day's input from `$AOC_DATA_DIR` (or `data/`), parsing each input
+/// only once per day.
+pub fn check(expected: &[Expected], puzzles: &[Box<dyn Puzzle>]) -> Vec<Check> {
+    let mut checks = Vec::with_capacity(expected.len());
+    let mut days: Vec<u8> = expected.iter().map(|exp| exp.day).collect();
+    days.sort_unstable();
+    days.dedup();
+
+    for day in days {
+        let day_expected: Vec<&Expected> = expected.iter().filter(|exp| exp.day == day).collect();
+        let parts: Vec<Part> = day_expected.iter().map(|exp| exp.part).collect();
+        let answers = solve(puzzles, day, &parts);
+
+        for (i, exp) in day_expected.into_iter().enumerate() {
+            checks.push(Check {
+                expected: exp.clone(),
+                actual: answers.clone().map(|answers| answers[i].clone()),
+            });
+        }
+    }
+    checks
+}
+
+fn solve(puzzles: &[Box<dyn Puzzle>], day: u8, parts: &[Part]) -> Result<Vec<Answer>, String> {
+    let puzzle = puzzles
+        .iter()
+        .find(|puzzle| puzzle.day() == day)
+        .ok_or_else(|| "day is not implemented".to_string())?;
+    let input = input::read(day, &Source::DataDir).map_err(|err| err.to_string())?;
+    puzzle.solve(&input, parts).map_err(|err| err.to_string())
+}
+
+pub fn print_table(checks: &[Check]) {
+    println!(
+        "{:>3}  {:<4}  {:<20}  {:<20}  result",
+        "day", "part", "expected", "actual"
+    );
+    for check in checks {
+        let (actual, result) = match &check.actual {
+            Ok(answer) if check.passed() => (answer.to_string(), "ok".to_string()),
+            Ok(answer) => (answer.to_string(), "FAIL".to_string()),
+            Err(err) => ("-".to_string(), format!("FAIL ({})", err)),
+        };
+        println!(
+            "{:>3}  {:<4}  {:<20}  {:<20}  {}",
+            check.expected.day, check.expected.part, check.expected.value, actual, result
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Check, Expected};
+    use crate::solution::{Answer, Part};
+
+    #[test]
+    fn parse_reads_numbers_and_text() {
+        let expected = parse(
+            "[[answers]]\nday = 14\npart = \"a\"\nexpected = 165\n\n\
+             [[answers]]\nday = 14\npart = \"B\"\nexpected = \"208\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            expected,
+            vec![
+                Expected {
+                    day: 14,
+                    part: Part::One,
+                    value: "165".to_string(),
+                },
+                Expected {
+                    day: 14,
+                    part: Part::Two,
+                    value: "208".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_parts() {
+        assert!(parse("[[answers]]\nday = 1\npart = \"c\"\nexpected = 1\n").is_err());
+        assert!(parse("[[answers]]\nday = 1\n").is_err());
+    }
+
+    #[test]
+    fn check_passes_only_on_equal_answers() {
+        let expected = Expected {
+            day: 1,
+            part: Part::One,
+            value: "514579".to_string(),
+        };
+        let check = |actual| Check {
+            expected: expected.clone(),
+            actual,
+        };
+        assert!(check(Ok(Answer::Number(514579))).passed());
+        assert!(!check(Ok(Answer::Number(1))).passed());
+        assert!(!check(Ok(Answer::None)).passed());
+        assert!(!check(Err("missing input".to_string())).passed());
+    }
+}
@@ -0,0 +1,41 @@
+use std::process::{Command, Output};
+
+fn aoc2020(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc2020"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("AOC_DATA_DIR", "fixtures")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn run_uses_the_given_day9_preamble() {
+    let output = aoc2020(&["run", "9", "-i", "fixtures/day9.txt", "--preamble", "5"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Day 9 - A: 127 - B: 62\n"
+    );
+
+    let output = aoc2020(&["run", "8", "--preamble", "5"]);
+    assert!(!output.status.success());
+    let output = aoc2020(&["run", "9", "--preamble", "x"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn verify_uses_the_given_day9_preamble() {
+    let output = aoc2020(&[
+        "verify",
+        "9",
+        "--answers",
+        "fixtures/answers.toml",
+        "--preamble",
+        "5",
+    ]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with("all 2 answers match\n"));
+}
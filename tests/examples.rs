@@ -0,0 +1,90 @@
+use aoc2020::{day9::Day9, registry, verify, Part, Puzzle};
+use std::{fs, path::PathBuf};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(name)
+}
+
+/// Days whose examples use different parameters from the real puzzle.
+fn puzzle(day: u8) -> Box<dyn Puzzle> {
+    match day {
+        9 => Box::new(Day9 { preamble: 5 }),
+        _ => registry::find(day).expect("day is registered"),
+    }
+}
+
+fn check_example(day: u8) {
+    let expected: Vec<_> = verify::load(&fixture("answers.toml"))
+        .unwrap()
+        .into_iter()
+        .filter(|exp| exp.day == day)
+        .collect();
+    let parts: Vec<Part> = expected.iter().map(|exp| exp.part).collect();
+    assert_eq!(parts, [Part::One, Part::Two], "day {} answers", day);
+
+    let input = fs::read_to_string(fixture(&format!("day{}.txt", day))).unwrap();
+    let answers = puzzle(day).solve(&input, &parts).unwrap();
+    for (exp, answer) in expected.iter().zip(answers) {
+        assert_eq!(
+            answer.to_string(),
+            exp.value,
+            "day {} part {}",
+            day,
+            exp.part
+        );
+    }
+}
+
+#[test]
+fn every_registered_day_has_an_example() {
+    for puzzle in registry::all() {
+        let day = puzzle.day();
+        assert!(fixture(&format!("day{}.txt", day)).is_file(), "day {}", day);
+    }
+}
+
+/// The published day 16 examples have no `departure` fields, so part two of
+/// `day16.txt` is an empty product. This variant of the part two example names
+/// two of them `departure ...` and adds an invalid ticket that must be dropped.
+#[test]
+fn day16_multiplies_the_departure_fields() {
+    let input = fs::read_to_string(fixture("day16_departure.txt")).unwrap();
+    let answers = puzzle(16).solve(&input, &[Part::One, Part::Two]).unwrap();
+    let answers: Vec<String> = answers.iter().map(ToString::to_string).collect();
+    assert_eq!(answers, ["20", "156"]);
+}
+
+macro_rules! examples {
+    ($($name:ident => $day:expr),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check_example($day);
+            }
+        )*
+    };
+}
+
+examples! {
+    day1 => 1,
+    day2 => 2,
+    day3 => 3,
+    day4 => 4,
+    day5 => 5,
+    day6 => 6,
+    day7 => 7,
+    day8 => 8,
+    day9 => 9,
+    day10 => 10,
+    day11 => 11,
+    day12 => 12,
+    day13 => 13,
+    day14 => 14,
+    day15 => 15,
+    day16 => 16,
+    day17 => 17,
+    day18 => 18,
+    day19 => 19,
+}
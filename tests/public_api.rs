@@ -0,0 +1,61 @@
+use aoc2020::{
+    day13::earliest_time,
+    day18::{advanced_binding_power, evaluate, infix_binding_power, EvalError, Lexer, Token},
+    day19::{compile_regex, Rules},
+    day7::Day7,
+    day8::{Day8, Program},
+    registry, Answer, Part, Solution,
+};
+
+#[test]
+fn program_runs_parsed_boot_code() {
+    let codes = Day8
+        .parse("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6")
+        .unwrap();
+    let mut program = Program::new(codes);
+    assert_eq!(program.exec(), 1);
+    assert_eq!(program.acc(), 5);
+    assert_eq!(program.exec(), 1);
+}
+
+#[test]
+fn lexer_feeds_the_pratt_parser() {
+    let mut lexer = Lexer::new("2 * 3 + (4 * 5)");
+    assert_eq!(evaluate(&mut lexer, 0, infix_binding_power), Ok(26));
+    let mut lexer = Lexer::new("2 * 3 + (4 * 5)");
+    assert_eq!(evaluate(&mut lexer, 0, advanced_binding_power), Ok(46));
+    let mut lexer = Lexer::new("2 * -");
+    assert_eq!(
+        evaluate(&mut lexer, 0, infix_binding_power),
+        Err(EvalError::Unexpected(Token::Op('-')))
+    );
+}
+
+#[test]
+fn rules_compile_to_regexes() {
+    let rules: Rules = [("0", "1 1"), ("1", "\"a\" ")]
+        .iter()
+        .map(|&(id, pattern)| (id.to_string(), pattern.trim().to_string()))
+        .collect();
+    let regex = compile_regex("0", &rules, true);
+    assert!(regex.is_match("aa"));
+    assert!(!regex.is_match("aaa"));
+}
+
+#[test]
+fn schedules_and_bag_rules_are_usable_directly() {
+    assert_eq!(earliest_time(vec![(0, 17), (2, 13), (3, 19)]), 3417);
+
+    let rules = Day7
+        .parse("shiny gold bags contain 2 dark red bags.\ndark red bags contain no other bags.")
+        .unwrap();
+    assert_eq!(rules[0].children[0].count, 2);
+    assert_eq!(rules[0].children[0].bag_type, "dark red");
+}
+
+#[test]
+fn registry_solves_any_day_by_number() {
+    let puzzle = registry::find(1).unwrap();
+    let answers = puzzle.solve("1721\n979\n366\n299\n675\n1456", &[Part::One]);
+    assert_eq!(answers, Ok(vec![Answer::Number(514579)]));
+}